@@ -0,0 +1,26 @@
+use crate::error::Result;
+use crate::models::{CalendarItem, TimeBlockConflict};
+use crate::services::CalendarService;
+use crate::state::AppState;
+use chrono::NaiveDate;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_calendar_range(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<Vec<CalendarItem>> {
+    CalendarService::get_range(&state.db, &state.settings(), start_date, end_date)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_time_block_conflicts(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<Vec<TimeBlockConflict>> {
+    CalendarService::conflicts(&state.db, &state.settings(), start_date, end_date)
+}
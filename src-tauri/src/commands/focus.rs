@@ -0,0 +1,89 @@
+use crate::db::FocusRepository;
+use crate::error::Result;
+use crate::models::{DailyFocusTotal, FocusPhase, FocusSession, FocusStatus, TaskFocusTotal};
+use crate::services::focus_service::FOCUS_CHANGED_EVENT;
+use crate::services::FocusService;
+use crate::state::AppState;
+use chrono::NaiveDate;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+/// 未指定数量时返回的专注记录条数
+const DEFAULT_SESSION_LIMIT: usize = 50;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_focus_status(state: State<'_, AppState>) -> Result<Option<FocusStatus>> {
+    Ok(state.focus.status())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn start_focus(
+    task_id: Option<String>,
+    phase: Option<FocusPhase>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<FocusStatus> {
+    let pomodoro = state.settings().pomodoro;
+    let status = state.focus.start(&state.db, &pomodoro, task_id.as_deref(), phase)?;
+    emit_focus_event(&app, FOCUS_CHANGED_EVENT, Some(status.clone()));
+    Ok(status)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn pause_focus(app: AppHandle, state: State<'_, AppState>) -> Result<FocusStatus> {
+    let status = state.focus.pause()?;
+    emit_focus_event(&app, FOCUS_CHANGED_EVENT, Some(status.clone()));
+    Ok(status)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn resume_focus(app: AppHandle, state: State<'_, AppState>) -> Result<FocusStatus> {
+    let status = state.focus.resume()?;
+    emit_focus_event(&app, FOCUS_CHANGED_EVENT, Some(status.clone()));
+    Ok(status)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn abandon_focus(app: AppHandle, state: State<'_, AppState>) -> Result<FocusSession> {
+    let session = state.focus.abandon(&state.db)?;
+    emit_focus_event(&app, FOCUS_CHANGED_EVENT, None::<FocusStatus>);
+    Ok(session)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_focus_sessions(
+    task_id: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FocusSession>> {
+    FocusRepository::get_recent(&state.db, task_id.as_deref(), limit.unwrap_or(DEFAULT_SESSION_LIMIT))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_task_focus_total(task_id: String, state: State<'_, AppState>) -> Result<TaskFocusTotal> {
+    FocusService::task_total(&state.db, &task_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_daily_focus_totals(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<Vec<DailyFocusTotal>> {
+    FocusService::daily_totals(&state.db, &state.settings(), start_date, end_date)
+}
+
+/// 向前端推送番茄钟事件，失败只记录日志
+pub(crate) fn emit_focus_event<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        tracing::warn!("failed to emit {}: {}", event, e);
+    }
+}
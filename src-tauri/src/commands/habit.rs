@@ -0,0 +1,59 @@
+use crate::db::HabitRepository;
+use crate::error::Result;
+use crate::models::{Habit, HabitCheckIn, HabitWithStats};
+use crate::services::HabitService;
+use crate::state::AppState;
+use chrono::NaiveDate;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_habits(include_archived: Option<bool>, state: State<'_, AppState>) -> Result<Vec<HabitWithStats>> {
+    HabitService::get_all(&state.db, &state.settings(), include_archived.unwrap_or(false))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_habit(habit_id: String, state: State<'_, AppState>) -> Result<HabitWithStats> {
+    HabitService::get(&state.db, &state.settings(), &habit_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(habit, state), fields(habit_id = %habit.id), err)]
+pub async fn create_habit(habit: Habit, state: State<'_, AppState>) -> Result<Habit> {
+    HabitService::create(&state.db, &state.settings(), &habit)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(habit, state), fields(habit_id = %habit.id), err)]
+pub async fn update_habit(habit: Habit, state: State<'_, AppState>) -> Result<Habit> {
+    HabitService::update(&state.db, &state.settings(), &habit)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_habit(habit_id: String, state: State<'_, AppState>) -> Result<()> {
+    HabitRepository::delete(&state.db, &habit_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn check_in_habit(
+    habit_id: String,
+    date: Option<NaiveDate>,
+    count: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<HabitWithStats> {
+    HabitService::check_in(&state.db, &state.settings(), &habit_id, date, count)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_habit_check_ins(
+    habit_id: String,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<Vec<HabitCheckIn>> {
+    HabitService::get_check_ins(&state.db, &habit_id, start_date, end_date)
+}
@@ -1,30 +1,106 @@
-use crate::db::ListRepository;
 use crate::error::Result;
-use crate::models::List;
+use crate::models::{DuplicateOptions, List, Settings, INBOX_LIST_ID};
+use crate::services::{ListService, SettingsService};
 use crate::state::AppState;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
+#[tracing::instrument(skip(list, state), fields(list_id = %list.id), err)]
 pub async fn create_list(list: List, state: State<'_, AppState>) -> Result<List> {
-    ListRepository::create(&state.db, &list)
+    ListService::create(&state.db, &list)
 }
 
 #[tauri::command]
-pub async fn get_lists(state: State<'_, AppState>) -> Result<Vec<List>> {
-    ListRepository::get_all(&state.db)
+#[tracing::instrument(skip(state), err)]
+pub async fn get_lists(
+    include_archived: Option<bool>,
+    include_hidden: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<List>> {
+    ListService::get_all(
+        &state.db,
+        state.settings().locale,
+        include_archived.unwrap_or(false),
+        include_hidden.unwrap_or(false),
+    )
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn get_list(list_id: String, state: State<'_, AppState>) -> Result<List> {
-    ListRepository::get_by_id(&state.db, &list_id)
+    ListService::get_by_id(&state.db, state.settings().locale, &list_id)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(list, state), fields(list_id = %list.id), err)]
 pub async fn update_list(list: List, state: State<'_, AppState>) -> Result<List> {
-    ListRepository::update(&state.db, &list)
+    ListService::update(&state.db, &list)
 }
 
 #[tauri::command]
-pub async fn delete_list(list_id: String, state: State<'_, AppState>) -> Result<()> {
-    ListRepository::delete(&state.db, &list_id)
+#[tracing::instrument(skip(state), err)]
+pub async fn duplicate_list(
+    list_id: String,
+    options: Option<DuplicateOptions>,
+    state: State<'_, AppState>,
+) -> Result<List> {
+    ListService::duplicate(&state.db, state.settings().locale, &list_id, &options.unwrap_or_default())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn reorder_list(
+    list_id: String,
+    prev_id: Option<String>,
+    next_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<List> {
+    ListService::reorder(&state.db, state.settings().locale, &list_id, prev_id.as_deref(), next_id.as_deref())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn delete_list(
+    list_id: String,
+    move_tasks_to: Option<String>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    ListService::delete(&state.db, &list_id, move_tasks_to.as_deref())?;
+
+    // 删除的是默认清单时，默认清单回退到收集箱
+    let settings = state.settings();
+    if settings.default_list_id == list_id {
+        let updated = SettingsService::update(
+            &state.db,
+            &Settings { default_list_id: INBOX_LIST_ID.to_string(), ..settings },
+        )?;
+        super::settings::publish(&app, &state, updated);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn archive_list(list_id: String, state: State<'_, AppState>) -> Result<()> {
+    ListService::set_archived(&state.db, &list_id, true)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn unarchive_list(list_id: String, state: State<'_, AppState>) -> Result<()> {
+    ListService::set_archived(&state.db, &list_id, false)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(name, state), err)]
+pub async fn rename_smart_list(list_id: String, name: Option<String>, state: State<'_, AppState>) -> Result<List> {
+    ListService::rename_smart_list(&state.db, state.settings().locale, &list_id, name.as_deref())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn set_smart_list_hidden(list_id: String, hidden: bool, state: State<'_, AppState>) -> Result<List> {
+    ListService::set_smart_list_hidden(&state.db, state.settings().locale, &list_id, hidden)
 }
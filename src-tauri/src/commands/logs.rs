@@ -0,0 +1,12 @@
+use crate::error::Result;
+use crate::logging;
+
+const DEFAULT_LOG_LINES: usize = 200;
+const MAX_LOG_LINES: usize = 5000;
+
+/// 返回最近的日志行，便于在反馈问题时附带
+#[tauri::command]
+#[tracing::instrument(err)]
+pub async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>> {
+    logging::recent_lines(lines.unwrap_or(DEFAULT_LOG_LINES).min(MAX_LOG_LINES))
+}
@@ -1,3 +1,12 @@
 pub mod task;
 pub mod list;
 pub mod tag;
+pub mod settings;
+pub mod template;
+pub mod focus;
+pub mod time;
+pub mod stats;
+pub mod habit;
+pub mod calendar;
+pub mod section;
+pub mod logs;
@@ -0,0 +1,44 @@
+use crate::error::Result;
+use crate::models::Section;
+use crate::services::SectionService;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_sections(list_id: String, state: State<'_, AppState>) -> Result<Vec<Section>> {
+    SectionService::get_by_list(&state.db, &list_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn create_section(list_id: String, name: String, state: State<'_, AppState>) -> Result<Section> {
+    SectionService::create(&state.db, &list_id, &name)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn rename_section(section_id: String, name: String, state: State<'_, AppState>) -> Result<Section> {
+    SectionService::rename(&state.db, &section_id, &name)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn reorder_section(
+    section_id: String,
+    prev_id: Option<String>,
+    next_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Section> {
+    SectionService::reorder(&state.db, &section_id, prev_id.as_deref(), next_id.as_deref())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_section(
+    section_id: String,
+    move_tasks_to: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    SectionService::delete(&state.db, &section_id, move_tasks_to.as_deref())
+}
@@ -0,0 +1,46 @@
+use crate::error::Result;
+use crate::i18n::Locale;
+use crate::logging;
+use crate::models::Settings;
+use crate::services::settings_service::SETTINGS_CHANGED_EVENT;
+use crate::services::SettingsService;
+use crate::state::AppState;
+use tauri::{AppHandle, Emitter, State};
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_settings(state: State<'_, AppState>) -> Result<Settings> {
+    Ok(state.settings())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(settings, app, state), err)]
+pub async fn update_settings(settings: Settings, app: AppHandle, state: State<'_, AppState>) -> Result<Settings> {
+    let updated = SettingsService::update(&state.db, &settings)?;
+    publish(&app, &state, updated.clone());
+    Ok(updated)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_locale(state: State<'_, AppState>) -> Result<Locale> {
+    Ok(state.settings().locale)
+}
+
+/// 只修改语言设置，其余设置保持不变
+#[tauri::command]
+#[tracing::instrument(skip(app, state), err)]
+pub async fn set_locale(locale: Locale, app: AppHandle, state: State<'_, AppState>) -> Result<()> {
+    let updated = SettingsService::update(&state.db, &Settings { locale, ..state.settings() })?;
+    publish(&app, &state, updated);
+    Ok(())
+}
+
+/// 更新内存中的设置并通知前端与后台任务
+pub(crate) fn publish(app: &AppHandle, state: &AppState, settings: Settings) {
+    logging::set_level(settings.log_level);
+    state.settings.send_replace(settings.clone());
+    if let Err(e) = app.emit(SETTINGS_CHANGED_EVENT, settings) {
+        tracing::warn!("failed to emit {}: {}", SETTINGS_CHANGED_EVENT, e);
+    }
+}
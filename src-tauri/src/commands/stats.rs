@@ -0,0 +1,18 @@
+use crate::error::Result;
+use crate::models::{Statistics, StatisticsRange};
+use crate::services::StatsService;
+use crate::state::AppState;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_statistics(range: StatisticsRange, state: State<'_, AppState>) -> Result<Statistics> {
+    StatsService::get(&state.db, &state.settings(), range)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn export_statistics_csv(range: StatisticsRange, state: State<'_, AppState>) -> Result<String> {
+    let stats = StatsService::get(&state.db, &state.settings(), range)?;
+    Ok(StatsService::to_csv(&stats))
+}
@@ -1,26 +1,73 @@
 use crate::db::TagRepository;
 use crate::error::Result;
-use crate::models::Tag;
+use crate::models::{Tag, TagNode, TagWithStats, TaggedTask};
+use crate::services::TagService;
 use crate::state::AppState;
 use tauri::State;
 
 #[tauri::command]
+#[tracing::instrument(skip(name, state), err)]
 pub async fn create_tag(name: String, color: String, parent_id: Option<String>, state: State<'_, AppState>) -> Result<Tag> {
     let tag = Tag::new(name, color, parent_id);
-    TagRepository::create(&state.db, &tag)
+    TagService::create(&state.db, &tag)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(tag, state), fields(tag_id = %tag.id), err)]
 pub async fn update_tag(tag: Tag, state: State<'_, AppState>) -> Result<Tag> {
-    TagRepository::update(&state.db, &tag)
+    TagService::update(&state.db, &tag)
 }
 
 #[tauri::command]
-pub async fn get_tags(state: State<'_, AppState>) -> Result<Vec<Tag>> {
-    TagRepository::get_all(&state.db)
+#[tracing::instrument(skip(state), err)]
+pub async fn get_tags(state: State<'_, AppState>) -> Result<Vec<TagWithStats>> {
+    TagRepository::get_all_with_stats(&state.db)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_recent_tags(limit: Option<usize>, state: State<'_, AppState>) -> Result<Vec<TagWithStats>> {
+    TagService::get_recent(&state.db, limit)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn reorder_tag(
+    tag_id: String,
+    prev_id: Option<String>,
+    next_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Tag> {
+    TagRepository::reorder(&state.db, &tag_id, prev_id.as_deref(), next_id.as_deref())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(names, state), fields(count = names.len()), err)]
+pub async fn tag_task_by_name(task_id: String, names: Vec<String>, state: State<'_, AppState>) -> Result<TaggedTask> {
+    let tagged = TagService::tag_task_by_name(&state.db, &task_id, &names)?;
+    Ok(TaggedTask { task: tagged.task.mark_overdue(&state.settings()), ..tagged })
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_tag_tree(state: State<'_, AppState>) -> Result<Vec<TagNode>> {
+    TagService::get_tree(&state.db)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn move_tag(tag_id: String, parent_id: Option<String>, state: State<'_, AppState>) -> Result<Tag> {
+    TagService::move_tag(&state.db, &tag_id, parent_id.as_deref())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn merge_tags(source_id: String, target_id: String, state: State<'_, AppState>) -> Result<Tag> {
+    TagService::merge(&state.db, &source_id, &target_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn delete_tag(tag_id: String, state: State<'_, AppState>) -> Result<()> {
     TagRepository::delete(&state.db, &tag_id)
 }
@@ -1,26 +1,36 @@
 use crate::db::TaskRepository;
 use crate::error::Result;
-use crate::models::Task;
+use crate::models::{
+    BulkItemResult, BulkTaskOperation, DuplicateOptions, OverdueReport, SectionGroup, Settings, Task, TaskListing,
+    TaskPatch,
+};
+use crate::services::{SectionService, TaskService};
 use crate::state::AppState;
 use tauri::State;
 
 #[tauri::command]
+#[tracing::instrument(skip(orders, state), err)]
 pub async fn update_task_orders(orders: Vec<(String, i32)>, state: State<'_, AppState>) -> Result<()> {
     TaskRepository::update_orders(&state.db, orders)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(task, state), fields(task_id = %task.id), err)]
 pub async fn create_task(task: Task, state: State<'_, AppState>) -> Result<Task> {
-    TaskRepository::create(&state.db, &task)
+    let settings = state.settings();
+    TaskService::create(&state.db, &settings, &task).map(|task| task.mark_overdue(&settings))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(title, state), err)]
 pub async fn create_task_simple(title: String, list_id: String, state: State<'_, AppState>) -> Result<Task> {
     let task = Task::new(title, list_id);
-    TaskRepository::create(&state.db, &task)
+    let settings = state.settings();
+    TaskService::create(&state.db, &settings, &task).map(|task| task.mark_overdue(&settings))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(title, repeat_rule, tags, description, state), err)]
 pub async fn create_task_extended(
     title: String,
     list_id: String,
@@ -41,84 +51,180 @@ pub async fn create_task_extended(
     }
     task.tags = tags;
     task.description = description.unwrap_or_default();
-    TaskRepository::create(&state.db, &task)
+    let settings = state.settings();
+    TaskService::create(&state.db, &settings, &task).map(|task| task.mark_overdue(&settings))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn get_subtasks(parent_id: String, state: State<'_, AppState>) -> Result<Vec<Task>> {
-    TaskRepository::get_by_parent(&state.db, &parent_id)
+    TaskRepository::get_by_parent(&state.db, &parent_id).map(|tasks| mark_overdue(tasks, &state.settings()))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(title, state), err)]
 pub async fn create_subtask_simple(title: String, parent_id: String, list_id: String, state: State<'_, AppState>) -> Result<Task> {
     let parent_task = TaskRepository::get_by_id(&state.db, &parent_id)?;
     let mut task = Task::new(title, list_id);
     task.parent_id = Some(parent_id);
     task.due_date = parent_task.due_date;
-    TaskRepository::create(&state.db, &task)
+    let settings = state.settings();
+    TaskService::create(&state.db, &settings, &task).map(|task| task.mark_overdue(&settings))
 }
 
 #[tauri::command]
-pub async fn get_tasks_by_tag(tag_id: String, state: State<'_, AppState>) -> Result<Vec<Task>> {
-    TaskRepository::get_by_tag(&state.db, &tag_id)
+#[tracing::instrument(skip(state), err)]
+pub async fn get_tasks_by_tag(
+    tag_id: String,
+    include_children: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Task>> {
+    TaskRepository::get_by_tag(&state.db, &tag_id, include_children.unwrap_or(false))
+        .map(|tasks| mark_overdue(tasks, &state.settings()))
 }
 
 #[tauri::command]
-pub async fn get_tasks(list_id: Option<String>, state: State<'_, AppState>) -> Result<Vec<Task>> {
-    match list_id {
-        Some(id) => TaskRepository::get_by_list(&state.db, &id),
-        None => TaskRepository::get_all(&state.db),
-    }
+#[tracing::instrument(skip(state), err)]
+pub async fn get_tasks(
+    list_id: Option<String>,
+    group_by_section: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<TaskListing> {
+    let settings = state.settings();
+    let tasks = match (list_id, group_by_section.unwrap_or(false)) {
+        // 看板视图：按分组返回各列任务
+        (Some(id), true) => {
+            let groups = SectionService::group_tasks(&state.db, &settings, &id)?
+                .into_iter()
+                .map(|group| SectionGroup { tasks: mark_overdue(group.tasks, &settings), ..group })
+                .collect();
+            return Ok(TaskListing::Grouped(groups));
+        }
+        (Some(id), false) => TaskRepository::get_by_list(&state.db, &id, &settings)?,
+        (None, _) => TaskRepository::get_all(&state.db)?,
+    };
+    Ok(TaskListing::Flat(mark_overdue(tasks, &settings)))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn get_task(task_id: String, state: State<'_, AppState>) -> Result<Task> {
-    TaskRepository::get_by_id(&state.db, &task_id)
+    TaskRepository::get_by_id(&state.db, &task_id).map(|task| task.mark_overdue(&state.settings()))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(task, state), fields(task_id = %task.id), err)]
 pub async fn update_task(task: Task, state: State<'_, AppState>) -> Result<Task> {
-    TaskRepository::update(&state.db, &task)
+    TaskService::update(&state.db, &task).map(|task| task.mark_overdue(&state.settings()))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(patch, state), err)]
+pub async fn patch_task(task_id: String, patch: TaskPatch, state: State<'_, AppState>) -> Result<Task> {
+    TaskService::patch(&state.db, &task_id, &patch).map(|task| task.mark_overdue(&state.settings()))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn duplicate_task(
+    task_id: String,
+    options: Option<DuplicateOptions>,
+    state: State<'_, AppState>,
+) -> Result<Task> {
+    TaskService::duplicate(&state.db, &task_id, &options.unwrap_or_default())
+        .map(|task| task.mark_overdue(&state.settings()))
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn reorder_task(
+    task_id: String,
+    prev_id: Option<String>,
+    next_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Task> {
+    TaskRepository::reorder(&state.db, &task_id, prev_id.as_deref(), next_id.as_deref())
+        .map(|task| task.mark_overdue(&state.settings()))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn move_task(
+    task_id: String,
+    list_id: Option<String>,
+    parent_id: Option<String>,
+    position: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Task> {
+    TaskService::move_task(&state.db, &task_id, list_id.as_deref(), parent_id.as_deref(), position)
+        .map(|task| task.mark_overdue(&state.settings()))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(task_ids, state), fields(count = task_ids.len()), err)]
+pub async fn bulk_update_tasks(
+    task_ids: Vec<String>,
+    operation: BulkTaskOperation,
+    state: State<'_, AppState>,
+) -> Result<Vec<BulkItemResult>> {
+    let settings = state.settings();
+    let mut results = TaskService::bulk_apply(&state.db, &task_ids, &operation)?;
+    for result in &mut results {
+        result.task = result.task.take().map(|task| task.mark_overdue(&settings));
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn delete_task(task_id: String, state: State<'_, AppState>) -> Result<()> {
     TaskRepository::delete(&state.db, &task_id)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn undo_delete_task(task_id: String, state: State<'_, AppState>) -> Result<()> {
     TaskRepository::undo_delete(&state.db, &task_id)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn delete_task_permanently(task_id: String, state: State<'_, AppState>) -> Result<()> {
     TaskRepository::delete_permanently(&state.db, &task_id)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn empty_trash(state: State<'_, AppState>) -> Result<()> {
     TaskRepository::empty_trash(&state.db)
 }
 
 #[tauri::command]
+#[tracing::instrument(skip(state), err)]
 pub async fn toggle_task(task_id: String, state: State<'_, AppState>) -> Result<Task> {
-    let mut task = TaskRepository::get_by_id(&state.db, &task_id)?;
-    task.toggle_completed();
-    let updated_task = TaskRepository::update(&state.db, &task)?;
-
-    // 如果父任务被完成，则同步完成所有未完成的子任务
-    if updated_task.completed {
-        let subtasks = TaskRepository::get_by_parent(&state.db, &task_id)?;
-        for mut subtask in subtasks {
-            if !subtask.completed {
-                subtask.completed = true;
-                subtask.completed_at = updated_task.completed_at;
-                subtask.updated_at = updated_task.updated_at;
-                TaskRepository::update(&state.db, &subtask)?;
-            }
-        }
+    // 完成父任务时同步完成所有未完成的子任务
+    TaskService::toggle(&state.db, &task_id).map(|task| task.mark_overdue(&state.settings()))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn postpone_overdue_tasks(state: State<'_, AppState>) -> Result<Vec<BulkItemResult>> {
+    let settings = state.settings();
+    let mut results = TaskService::postpone_overdue(&state.db, &settings)?;
+    for result in &mut results {
+        result.task = result.task.take().map(|task| task.mark_overdue(&settings));
     }
+    Ok(results)
+}
+
+/// 启动检查尚未完成时返回 `None`
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_overdue_report(state: State<'_, AppState>) -> Result<Option<OverdueReport>> {
+    Ok(state.overdue_report.get().cloned())
+}
 
-    Ok(updated_task)
+/// 返回前按当前时间标记逾期任务
+fn mark_overdue(tasks: Vec<Task>, settings: &Settings) -> Vec<Task> {
+    tasks.into_iter().map(|task| task.mark_overdue(settings)).collect()
 }
@@ -0,0 +1,69 @@
+use crate::db::TemplateRepository;
+use crate::error::Result;
+use crate::models::{Template, TemplateInstance};
+use crate::services::TemplateService;
+use crate::state::AppState;
+use std::collections::HashMap;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_templates(state: State<'_, AppState>) -> Result<Vec<Template>> {
+    TemplateRepository::get_all(&state.db)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_template(template_id: String, state: State<'_, AppState>) -> Result<Template> {
+    TemplateRepository::get_by_id(&state.db, &template_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(template, state), fields(template_id = %template.id), err)]
+pub async fn create_template(template: Template, state: State<'_, AppState>) -> Result<Template> {
+    TemplateService::create(&state.db, &template)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(template, state), fields(template_id = %template.id), err)]
+pub async fn update_template(template: Template, state: State<'_, AppState>) -> Result<Template> {
+    TemplateService::update(&state.db, &template)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_template(template_id: String, state: State<'_, AppState>) -> Result<()> {
+    TemplateRepository::delete(&state.db, &template_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(name, state), err)]
+pub async fn save_task_as_template(task_id: String, name: String, state: State<'_, AppState>) -> Result<Template> {
+    TemplateService::save_task(&state.db, &state.settings(), &task_id, &name)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(name, state), err)]
+pub async fn save_list_as_template(list_id: String, name: String, state: State<'_, AppState>) -> Result<Template> {
+    TemplateService::save_list(&state.db, &state.settings(), &list_id, &name)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(variables, state), err)]
+pub async fn instantiate_template(
+    template_id: String,
+    list_id: Option<String>,
+    variables: Option<HashMap<String, String>>,
+    state: State<'_, AppState>,
+) -> Result<TemplateInstance> {
+    let settings = state.settings();
+    let instance = TemplateService::instantiate(
+        &state.db,
+        &settings,
+        &template_id,
+        list_id.as_deref(),
+        &variables.unwrap_or_default(),
+    )?;
+    let tasks = instance.tasks.into_iter().map(|task| task.mark_overdue(&settings)).collect();
+    Ok(TemplateInstance { tasks, ..instance })
+}
@@ -0,0 +1,65 @@
+use crate::db::TimeEntryRepository;
+use crate::error::Result;
+use crate::models::{TaskTimeSummary, TimeEntry, TimeReport};
+use crate::services::TimeService;
+use crate::state::AppState;
+use chrono::NaiveDate;
+use tauri::State;
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn start_time_entry(task_id: String, state: State<'_, AppState>) -> Result<TimeEntry> {
+    TimeService::start(&state.db, &task_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn stop_time_entry(state: State<'_, AppState>) -> Result<TimeEntry> {
+    TimeService::stop(&state.db)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_running_time_entry(state: State<'_, AppState>) -> Result<Option<TimeEntry>> {
+    TimeEntryRepository::get_running(&state.db)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(note, state), err)]
+pub async fn log_time_entry(
+    task_id: String,
+    started_at: i64,
+    ended_at: i64,
+    note: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<TimeEntry> {
+    TimeService::log(&state.db, &task_id, started_at, ended_at, note.as_deref().unwrap_or_default())
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_time_entries(task_id: String, state: State<'_, AppState>) -> Result<Vec<TimeEntry>> {
+    TimeEntryRepository::get_by_task(&state.db, &task_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn delete_time_entry(entry_id: String, state: State<'_, AppState>) -> Result<()> {
+    TimeEntryRepository::delete(&state.db, &entry_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_task_time_summary(task_id: String, state: State<'_, AppState>) -> Result<TaskTimeSummary> {
+    TimeService::summary(&state.db, &task_id)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip(state), err)]
+pub async fn get_time_report(
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    state: State<'_, AppState>,
+) -> Result<TimeReport> {
+    TimeService::report(&state.db, &state.settings(), start_date, end_date)
+}
@@ -39,6 +39,14 @@ impl Database {
                 updated_at INTEGER NOT NULL,
                 completed_at INTEGER,
                 repeat_rule TEXT,
+                deleted_at INTEGER,
+                version INTEGER NOT NULL DEFAULT 1,
+                sort_key TEXT NOT NULL DEFAULT '',
+                estimated_minutes INTEGER,
+                start_date INTEGER,
+                duration_minutes INTEGER,
+                is_all_day INTEGER NOT NULL DEFAULT 0,
+                section_id TEXT REFERENCES sections(id) ON DELETE SET NULL,
                 FOREIGN KEY (list_id) REFERENCES lists(id) ON DELETE CASCADE,
                 FOREIGN KEY (parent_id) REFERENCES tasks(id) ON DELETE CASCADE
             )",
@@ -51,6 +59,31 @@ impl Database {
         // 确保 repeat_rule 列存在
         let _ = conn.execute("ALTER TABLE tasks ADD COLUMN repeat_rule TEXT", []);
 
+        // 移入垃圾桶的时间，用于按保留期限自动清理；升级前已在垃圾桶中的任务从本次升级开始计算保留期
+        if conn.execute("ALTER TABLE tasks ADD COLUMN deleted_at INTEGER", []).is_ok() {
+            conn.execute(
+                "UPDATE tasks SET deleted_at = ?1 WHERE is_deleted = 1",
+                [chrono::Utc::now().timestamp()],
+            )?;
+        }
+
+        // 乐观锁版本号
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN version INTEGER NOT NULL DEFAULT 1", []);
+
+        // 分数索引排序键
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN sort_key TEXT NOT NULL DEFAULT ''", []);
+
+        // 预估耗时（分钟）
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN estimated_minutes INTEGER", []);
+
+        // 开始时间、时间块时长与全天标记
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN start_date INTEGER", []);
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN duration_minutes INTEGER", []);
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN is_all_day INTEGER NOT NULL DEFAULT 0", []);
+
+        // 看板分组
+        let _ = conn.execute("ALTER TABLE tasks ADD COLUMN section_id TEXT REFERENCES sections(id) ON DELETE SET NULL", []);
+
         // 创建清单表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS lists (
@@ -60,13 +93,25 @@ impl Database {
                 color TEXT NOT NULL,
                 is_smart INTEGER NOT NULL DEFAULT 0,
                 order_num INTEGER NOT NULL DEFAULT 0,
-                created_at INTEGER NOT NULL
+                is_archived INTEGER NOT NULL DEFAULT 0,
+                is_deleted INTEGER NOT NULL DEFAULT 0,
+                is_hidden INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1,
+                sort_key TEXT NOT NULL DEFAULT ''
             )",
             [],
         )?;
 
         // 确保 is_smart 列存在 (简单迁移)
         let _ = conn.execute("ALTER TABLE lists ADD COLUMN is_smart INTEGER NOT NULL DEFAULT 0", []);
+        // 归档与软删除标记
+        let _ = conn.execute("ALTER TABLE lists ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE lists ADD COLUMN is_deleted INTEGER NOT NULL DEFAULT 0", []);
+        // 智能清单隐藏标记
+        let _ = conn.execute("ALTER TABLE lists ADD COLUMN is_hidden INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE lists ADD COLUMN version INTEGER NOT NULL DEFAULT 1", []);
+        let _ = conn.execute("ALTER TABLE lists ADD COLUMN sort_key TEXT NOT NULL DEFAULT ''", []);
 
         // 强制修复：如果存在 order_num 为 0 的多个智能清单，可能导致排序混乱或丢失
         // 我们可以根据 id 重新设置 order_num
@@ -76,25 +121,29 @@ impl Database {
         let _ = conn.execute("UPDATE lists SET order_num = 3 WHERE id = 'smart_inbox'", []);
         let _ = conn.execute("UPDATE lists SET order_num = 4 WHERE id = 'smart_completed'", []);
         let _ = conn.execute("UPDATE lists SET order_num = 5 WHERE id = 'smart_trash'", []);
+        let _ = conn.execute("UPDATE lists SET order_num = 6 WHERE id = 'smart_overdue'", []);
 
-        // 彻底解决：如果仍然没有垃圾桶，可能是因为 INSERT OR REPLACE 失败或被其他逻辑覆盖
-        // 我们直接执行一个显式的插入，并打印受影响的行数
-        let rows = conn.execute(
-            "INSERT OR REPLACE INTO lists (id, name, icon, color, is_smart, order_num, created_at)
-             VALUES ('smart_trash', '垃圾桶', '🗑️', '#3B82F6', 1, 5, ?1)",
-            rusqlite::params![chrono::Utc::now().timestamp()],
+        // 创建设置表
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )",
+            [],
         )?;
-        println!("Rust: Force inserted smart_trash, rows affected: {}", rows);
 
         // 创建标签表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tags (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL UNIQUE,
+                name_key TEXT,
                 color TEXT NOT NULL,
                 parent_id TEXT,
                 is_pinned INTEGER NOT NULL DEFAULT 0,
                 created_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1,
+                sort_key TEXT NOT NULL DEFAULT '',
                 FOREIGN KEY (parent_id) REFERENCES tags(id) ON DELETE SET NULL
             )",
             [],
@@ -104,12 +153,107 @@ impl Database {
         let _ = conn.execute("ALTER TABLE tags ADD COLUMN parent_id TEXT", []);
         // 确保 is_pinned 列存在 (简单迁移)
         let _ = conn.execute("ALTER TABLE tags ADD COLUMN is_pinned INTEGER NOT NULL DEFAULT 0", []);
+        let _ = conn.execute("ALTER TABLE tags ADD COLUMN version INTEGER NOT NULL DEFAULT 1", []);
+        let _ = conn.execute("ALTER TABLE tags ADD COLUMN sort_key TEXT NOT NULL DEFAULT ''", []);
+        // 规范化名称键，保证名称忽略大小写唯一
+        let _ = conn.execute("ALTER TABLE tags ADD COLUMN name_key TEXT", []);
+
+        // 创建模板表，清单信息与任务树以 JSON 保存
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS templates (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                list TEXT,
+                tasks TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1
+            )",
+            [],
+        )?;
+
+        // 创建番茄钟专注记录表；任务被永久删除后保留记录用于统计
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS focus_sessions (
+                id TEXT PRIMARY KEY,
+                task_id TEXT,
+                phase TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                planned_secs INTEGER NOT NULL,
+                focused_secs INTEGER NOT NULL,
+                started_at INTEGER NOT NULL,
+                ended_at INTEGER NOT NULL,
+                FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE SET NULL
+            )",
+            [],
+        )?;
+
+        // 创建任务计时记录表，ended_at 为空表示计时中
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS time_entries (
+                id TEXT PRIMARY KEY,
+                task_id TEXT NOT NULL,
+                started_at INTEGER NOT NULL,
+                ended_at INTEGER,
+                note TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // 创建习惯表，打卡频率以 JSON 保存
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS habits (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                icon TEXT NOT NULL DEFAULT '',
+                color TEXT NOT NULL,
+                frequency TEXT NOT NULL,
+                goal_count INTEGER NOT NULL DEFAULT 1,
+                start_date TEXT NOT NULL,
+                is_archived INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1
+            )",
+            [],
+        )?;
+
+        // 创建习惯打卡表，每个习惯每天一行
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS habit_check_ins (
+                habit_id TEXT NOT NULL,
+                date TEXT NOT NULL,
+                count INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (habit_id, date),
+                FOREIGN KEY (habit_id) REFERENCES habits(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // 创建看板分组表，分组属于某个清单
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sections (
+                id TEXT PRIMARY KEY,
+                list_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                sort_key TEXT NOT NULL DEFAULT '',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1,
+                FOREIGN KEY (list_id) REFERENCES lists(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
 
         // 创建任务标签关联表
         conn.execute(
             "CREATE TABLE IF NOT EXISTS task_tags (
                 task_id TEXT NOT NULL,
                 tag_id TEXT NOT NULL,
+                tagged_at INTEGER,
                 PRIMARY KEY (task_id, tag_id),
                 FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
                 FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
@@ -117,6 +261,14 @@ impl Database {
             [],
         )?;
 
+        // 打标签的时间，用于「最近使用的标签」；旧数据以任务的更新时间近似
+        if conn.execute("ALTER TABLE task_tags ADD COLUMN tagged_at INTEGER", []).is_ok() {
+            conn.execute(
+                "UPDATE task_tags SET tagged_at = (SELECT updated_at FROM tasks WHERE tasks.id = task_tags.task_id)",
+                [],
+            )?;
+        }
+
         // 创建索引
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_tasks_list_id ON tasks(list_id)",
@@ -134,51 +286,233 @@ impl Database {
             "CREATE INDEX IF NOT EXISTS idx_tasks_completed ON tasks(completed)",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_sort_key ON tasks(list_id, parent_id, sort_key)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_section_id ON tasks(section_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sections_list_id ON sections(list_id, sort_key)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_task_tags_tag_id ON task_tags(tag_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_time_entries_task_id ON time_entries(task_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_time_entries_started_at ON time_entries(started_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_focus_sessions_task_id ON focus_sessions(task_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_focus_sessions_started_at ON focus_sessions(started_at)",
+            [],
+        )?;
 
         // 初始化智能清单
         self.init_smart_lists(&conn)?;
 
+        // 只需执行一次的历史数据修复
+        Self::run_data_migrations(&conn)?;
+
+        // 旧数据没有排序键时，按原有的 order_num 顺序一次性分配
+        Self::backfill_sort_keys(&conn, "tasks", "order_num ASC, created_at DESC")?;
+        Self::backfill_sort_keys(&conn, "lists", "order_num ASC, created_at ASC")?;
+        Self::backfill_sort_keys(&conn, "tags", "name ASC")?;
+
+        Self::normalize_tag_names(&conn)?;
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_tags_name_key ON tags(name_key)",
+            [],
+        )?;
+
         Ok(())
     }
 
     fn init_smart_lists(&self, conn: &Connection) -> Result<()> {
-        use crate::models::{List, SmartListType};
-        
-        let smart_types = vec![
-            SmartListType::All,
-            SmartListType::Today,
-            SmartListType::Week,
-            SmartListType::Inbox,
-            SmartListType::Completed,
-            SmartListType::Trash,
-        ];
+        use crate::models::{List, SmartListType, INBOX_LIST_ID};
 
         let now = chrono::Utc::now().timestamp();
 
-        for (index, list_type) in smart_types.into_iter().enumerate() {
+        // 只补齐缺失的智能清单，不覆盖用户的重命名 / 隐藏设置。
+        // 名称留空表示使用当前语言的默认名称，在读取时解析。
+        for (index, list_type) in SmartListType::ALL.into_iter().enumerate() {
             let list = List::new_smart(list_type);
-            println!("Initializing smart list: {} (id: {})", list.name, list.id);
-            conn.execute(
-                "INSERT OR REPLACE INTO lists (id, name, icon, color, is_smart, order_num, created_at)
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO lists (id, name, icon, color, is_smart, order_num, created_at)
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                 rusqlite::params![list.id, list.name, list.icon, list.color, 1, index as i32, now],
             )?;
+
+            // 新版本新增的智能清单排在最后，避免触发全量回填而打乱用户调整过的顺序
+            if inserted > 0 {
+                let last_key: Option<String> =
+                    conn.query_row("SELECT MAX(sort_key) FROM lists WHERE sort_key != ''", [], |row| row.get(0))?;
+                // 已有的键无效时保持为空，由随后的回填统一重新分配
+                if let Some(sort_key) = last_key.and_then(|key| crate::ordering::key_between(Some(&key), None).ok()) {
+                    conn.execute("UPDATE lists SET sort_key = ?1 WHERE id = ?2", rusqlite::params![sort_key, list.id])?;
+                }
+            }
+        }
+
+        // 收集箱对应的真实清单；历史上直接挂在 smart_inbox 下的任务迁移过去
+        conn.execute(
+            "INSERT OR IGNORE INTO lists (id, name, icon, color, is_smart, order_num, created_at)
+             VALUES (?1, '', '📥', '#3B82F6', 0, 0, ?2)",
+            rusqlite::params![INBOX_LIST_ID, now],
+        )?;
+        conn.execute(
+            "UPDATE tasks SET list_id = ?1 WHERE list_id = ?2",
+            rusqlite::params![INBOX_LIST_ID, SmartListType::Inbox.id()],
+        )?;
+
+        let smart_count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM lists WHERE is_smart = 1", [], |row| row.get(0))?;
+        tracing::debug!(smart_count, "smart lists initialized");
+
+        Ok(())
+    }
+
+    /// 按顺序执行尚未执行过的一次性数据迁移，已执行到的序号记录在 `PRAGMA user_version` 中
+    fn run_data_migrations(conn: &Connection) -> Result<()> {
+        let migrations: [fn(&Connection) -> Result<()>; 2] =
+            [Self::repair_subtask_lists, Self::clear_legacy_list_names];
+
+        let applied: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (index, migration) in migrations.iter().enumerate().skip(applied) {
+            let tx = conn.unchecked_transaction()?;
+            migration(&tx)?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+        }
+
+        Ok(())
+    }
+
+    /// 修复历史数据：子任务与根任务不在同一清单时，统一归入根任务所在清单
+    fn repair_subtask_lists(conn: &Connection) -> Result<()> {
+        let repaired = conn.execute(
+            "WITH RECURSIVE tree(id, root_list) AS (
+                SELECT id, list_id FROM tasks WHERE parent_id IS NULL
+                UNION ALL
+                SELECT t.id, tree.root_list FROM tasks t JOIN tree ON t.parent_id = tree.id
+            )
+            UPDATE tasks SET list_id = tree.root_list FROM tree
+            WHERE tree.id = tasks.id AND tasks.parent_id IS NOT NULL AND tasks.list_id != tree.root_list",
+            [],
+        )?;
+        tracing::debug!(repaired, "subtasks moved into their root task's list");
+
+        Ok(())
+    }
+
+    /// 旧版本每次启动都会写入硬编码的中文名称，清空后改为按语言解析
+    fn clear_legacy_list_names(conn: &Connection) -> Result<()> {
+        use crate::i18n::{self, Locale, Message};
+        use crate::models::{SmartListType, INBOX_LIST_ID};
+
+        let legacy_names = SmartListType::ALL
+            .into_iter()
+            .map(|list_type| (list_type.id(), Message::SmartListName(list_type)))
+            .chain([(INBOX_LIST_ID.to_string(), Message::InboxListName)]);
+        for (list_id, message) in legacy_names {
+            conn.execute(
+                "UPDATE lists SET name = '' WHERE id = ?1 AND name = ?2",
+                rusqlite::params![list_id, i18n::translate(Locale::ZhCn, message)],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// 补齐标签的规范化名称键。
+    ///
+    /// 旧版本允许 "Work" 与 "work" 这类仅大小写不同的标签并存，这里把它们合并到最早创建的那个。
+    fn normalize_tag_names(conn: &Connection) -> Result<()> {
+        use crate::db::{tag_repo::TAG_SUBTREE_SQL, TagRepository};
+        use crate::models::Tag;
+        use std::collections::HashMap;
+
+        let missing: i64 = conn.query_row("SELECT COUNT(*) FROM tags WHERE name_key IS NULL", [], |row| row.get(0))?;
+        if missing == 0 {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let tags = tx
+            .prepare("SELECT id, name FROM tags ORDER BY created_at ASC, id ASC")?
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<(String, String)>>>()?;
+
+        let mut keepers: HashMap<String, (String, String)> = HashMap::new();
+        let mut merged = 0;
+        for (id, name) in tags {
+            let key = Tag::name_key(&name);
+            match keepers.get(&key) {
+                Some((keeper_id, _)) => {
+                    // 保留的标签位于重名标签的子树中时先移出，避免合并后出现环
+                    tx.execute(
+                        &format!(
+                            "UPDATE tags SET parent_id = (SELECT parent_id FROM tags WHERE id = ?1)
+                             WHERE id = ?2 AND id IN ({})",
+                            TAG_SUBTREE_SQL
+                        ),
+                        rusqlite::params![id, keeper_id],
+                    )?;
+                    TagRepository::merge_into(&tx, &id, keeper_id)?;
+                    merged += 1;
+                }
+                None => {
+                    keepers.insert(key, (id, Tag::normalize_name(&name)));
+                }
+            }
+        }
+
+        // 重名标签合并完后规范化名称不会再冲突
+        for (key, (id, name)) in &keepers {
+            tx.execute(
+                "UPDATE tags SET name = ?1, name_key = ?2 WHERE id = ?3",
+                rusqlite::params![name, key, id],
+            )?;
         }
+        tx.commit()?;
+
+        tracing::info!(tags = keepers.len(), merged, "normalized tag names");
+        Ok(())
+    }
+
+    fn backfill_sort_keys(conn: &Connection, table: &str, order_by: &str) -> Result<()> {
+        let missing: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE sort_key = ''", table),
+            [],
+            |row| row.get(0),
+        )?;
+        if missing == 0 {
+            return Ok(());
+        }
+
+        let ids = conn
+            .prepare(&format!("SELECT id FROM {} ORDER BY {}", table, order_by))?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<String>>>()?;
 
-        // 验证插入结果
-        let mut stmt = conn.prepare("SELECT id, name FROM lists WHERE is_smart = 1")?;
-        let rows = stmt.query_map([], |row| {
-            let id: String = row.get(0)?;
-            let name: String = row.get(1)?;
-            Ok((id, name))
-        })?;
-
-        println!("Smart lists in database after init:");
-        for row in rows {
-            let (id, name) = row?;
-            println!("  - {}: {}", id, name);
+        let keys = crate::ordering::spread_keys(ids.len());
+        let mut stmt = conn.prepare(&format!("UPDATE {} SET sort_key = ?1 WHERE id = ?2", table))?;
+        for (id, key) in ids.iter().zip(&keys) {
+            stmt.execute(rusqlite::params![key, id])?;
         }
 
+        tracing::info!(table, rows = ids.len(), "assigned sort keys");
         Ok(())
     }
 }
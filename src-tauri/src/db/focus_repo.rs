@@ -0,0 +1,103 @@
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{FocusOutcome, FocusPhase, FocusSession, TaskFocusTotal};
+use rusqlite::params;
+
+const FOCUS_COLUMNS: &str = "id, task_id, phase, outcome, planned_secs, focused_secs, started_at, ended_at";
+
+/// 番茄钟专注记录
+pub struct FocusRepository;
+
+impl FocusRepository {
+    /// 写入一条已结束的记录；关联的任务已被永久删除时不再关联任务
+    pub fn insert(db: &Database, session: &FocusSession) -> Result<FocusSession> {
+        let conn = db.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO focus_sessions (id, task_id, phase, outcome, planned_secs, focused_secs, started_at, ended_at)
+             VALUES (?1, (SELECT id FROM tasks WHERE id = ?2), ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                session.id,
+                session.task_id,
+                session.phase.as_str(),
+                session.outcome.as_str(),
+                session.planned_secs,
+                session.focused_secs,
+                session.started_at,
+                session.ended_at,
+            ],
+        )?;
+
+        let task_id: Option<String> = conn.query_row(
+            "SELECT task_id FROM focus_sessions WHERE id = ?1",
+            params![session.id],
+            |row| row.get(0),
+        )?;
+
+        Ok(FocusSession { task_id, ..session.clone() })
+    }
+
+    /// 最近的专注记录，可按任务过滤
+    pub fn get_recent(db: &Database, task_id: Option<&str>, limit: usize) -> Result<Vec<FocusSession>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM focus_sessions
+             WHERE ?1 IS NULL OR task_id = ?1
+             ORDER BY started_at DESC
+             LIMIT ?2",
+            FOCUS_COLUMNS
+        ))?;
+        let sessions = stmt
+            .query_map(params![task_id, limit as i64], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<FocusSession>>>()?;
+
+        Ok(sessions)
+    }
+
+    /// 开始时间落在 `[start, end]` 内的工作阶段记录
+    pub fn get_work_sessions_between(db: &Database, start: i64, end: i64) -> Result<Vec<FocusSession>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM focus_sessions
+             WHERE phase = ?1 AND started_at BETWEEN ?2 AND ?3
+             ORDER BY started_at ASC",
+            FOCUS_COLUMNS
+        ))?;
+        let sessions = stmt
+            .query_map(params![FocusPhase::Work.as_str(), start, end], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<FocusSession>>>()?;
+
+        Ok(sessions)
+    }
+
+    pub fn task_total(db: &Database, task_id: &str) -> Result<TaskFocusTotal> {
+        let conn = db.conn.lock().unwrap();
+
+        let (focused_secs, completed_sessions) = conn.query_row(
+            "SELECT COALESCE(SUM(focused_secs), 0), COALESCE(SUM(outcome = ?3), 0)
+             FROM focus_sessions WHERE task_id = ?1 AND phase = ?2",
+            params![task_id, FocusPhase::Work.as_str(), FocusOutcome::Completed.as_str()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(TaskFocusTotal { task_id: task_id.to_string(), focused_secs, completed_sessions })
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<FocusSession> {
+        let phase: String = row.get(2)?;
+        let outcome: String = row.get(3)?;
+
+        Ok(FocusSession {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            phase: FocusPhase::parse(&phase).unwrap_or(FocusPhase::Work),
+            outcome: FocusOutcome::parse(&outcome).unwrap_or(FocusOutcome::Abandoned),
+            planned_secs: row.get(4)?,
+            focused_secs: row.get(5)?,
+            started_at: row.get(6)?,
+            ended_at: row.get(7)?,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::{Habit, HabitCheckIn};
+use chrono::NaiveDate;
+use rusqlite::{params, OptionalExtension};
+
+const HABIT_COLUMNS: &str =
+    "id, name, icon, color, frequency, goal_count, start_date, is_archived, created_at, updated_at, version";
+
+/// 习惯与打卡记录，日期以 `YYYY-MM-DD` 文本保存
+pub struct HabitRepository;
+
+impl HabitRepository {
+    pub fn create(db: &Database, habit: &Habit) -> Result<Habit> {
+        let conn = db.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO habits (id, name, icon, color, frequency, goal_count, start_date, is_archived,
+                                 created_at, updated_at, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 1)",
+            params![
+                habit.id,
+                habit.name,
+                habit.icon,
+                habit.color,
+                serde_json::to_string(&habit.frequency)?,
+                habit.goal_count,
+                habit.start_date.to_string(),
+                habit.is_archived,
+                habit.created_at,
+                habit.updated_at,
+            ],
+        )?;
+
+        Ok(Habit { version: 1, ..habit.clone() })
+    }
+
+    pub fn get_by_id(db: &Database, habit_id: &str) -> Result<Habit> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::find(&conn, habit_id)?.ok_or_else(|| AppError::NotFound(format!("Habit {} not found", habit_id)))
+    }
+
+    pub fn get_all(db: &Database, include_archived: bool) -> Result<Vec<Habit>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM habits WHERE ?1 OR is_archived = 0 ORDER BY is_archived ASC, created_at ASC",
+            HABIT_COLUMNS
+        ))?;
+        let habits = stmt
+            .query_map(params![include_archived], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Habit>>>()?;
+
+        Ok(habits)
+    }
+
+    /// 更新习惯。`habit.version` 必须与数据库中的版本一致，否则返回冲突
+    pub fn update(db: &Database, habit: &Habit) -> Result<Habit> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute(
+            "UPDATE habits SET name = ?1, icon = ?2, color = ?3, frequency = ?4, goal_count = ?5, start_date = ?6,
+                    is_archived = ?7, updated_at = ?8, version = version + 1
+             WHERE id = ?9 AND version = ?10",
+            params![
+                habit.name,
+                habit.icon,
+                habit.color,
+                serde_json::to_string(&habit.frequency)?,
+                habit.goal_count,
+                habit.start_date.to_string(),
+                habit.is_archived,
+                habit.updated_at,
+                habit.id,
+                habit.version,
+            ],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(match Self::find(&conn, &habit.id)? {
+                Some(current) => AppError::conflict(
+                    format!("Habit {} was modified (current version {})", habit.id, current.version),
+                    &current,
+                ),
+                None => AppError::NotFound(format!("Habit {} not found", habit.id)),
+            });
+        }
+
+        Ok(Habit { version: habit.version + 1, ..habit.clone() })
+    }
+
+    /// 删除习惯及其全部打卡记录
+    pub fn delete(db: &Database, habit_id: &str) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute("DELETE FROM habits WHERE id = ?1", params![habit_id])?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Habit {} not found", habit_id)));
+        }
+
+        Ok(())
+    }
+
+    /// 设置某天的打卡次数，次数为 0 时删除该天的记录
+    pub fn set_check_in(db: &Database, habit_id: &str, date: NaiveDate, count: i32, now: i64) -> Result<HabitCheckIn> {
+        let conn = db.conn.lock().unwrap();
+
+        if count > 0 {
+            conn.execute(
+                "INSERT INTO habit_check_ins (habit_id, date, count, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(habit_id, date) DO UPDATE SET count = excluded.count, updated_at = excluded.updated_at",
+                params![habit_id, date.to_string(), count, now],
+            )?;
+        } else {
+            conn.execute(
+                "DELETE FROM habit_check_ins WHERE habit_id = ?1 AND date = ?2",
+                params![habit_id, date.to_string()],
+            )?;
+        }
+
+        Ok(HabitCheckIn { habit_id: habit_id.to_string(), date, count: count.max(0), updated_at: now })
+    }
+
+    /// 在某天的打卡次数上加一，返回新的次数；已达到 `max_count` 时不修改并返回 `None`
+    pub fn increment_check_in(
+        db: &Database,
+        habit_id: &str,
+        date: NaiveDate,
+        max_count: i32,
+        now: i64,
+    ) -> Result<Option<i32>> {
+        let conn = db.conn.lock().unwrap();
+
+        let count = conn
+            .query_row(
+                "INSERT INTO habit_check_ins (habit_id, date, count, updated_at) VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(habit_id, date) DO UPDATE SET count = count + 1, updated_at = excluded.updated_at
+                 WHERE habit_check_ins.count < ?4
+                 RETURNING count",
+                params![habit_id, date.to_string(), now, max_count],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(count)
+    }
+
+    /// 全部打卡次数之和
+    pub fn total_check_ins(db: &Database, habit_id: &str) -> Result<i64> {
+        let conn = db.conn.lock().unwrap();
+
+        let total = conn.query_row(
+            "SELECT COALESCE(SUM(count), 0) FROM habit_check_ins WHERE habit_id = ?1",
+            params![habit_id],
+            |row| row.get(0),
+        )?;
+
+        Ok(total)
+    }
+
+    /// `[start, end]` 内的打卡记录，按日期升序
+    pub fn get_check_ins(db: &Database, habit_id: &str, start: NaiveDate, end: NaiveDate) -> Result<Vec<HabitCheckIn>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT habit_id, date, count, updated_at FROM habit_check_ins
+             WHERE habit_id = ?1 AND date BETWEEN ?2 AND ?3
+             ORDER BY date ASC",
+        )?;
+        let check_ins = stmt
+            .query_map(params![habit_id, start.to_string(), end.to_string()], |row| {
+                Ok(HabitCheckIn {
+                    habit_id: row.get(0)?,
+                    date: parse_date(row, 1)?,
+                    count: row.get(2)?,
+                    updated_at: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<HabitCheckIn>>>()?;
+
+        Ok(check_ins)
+    }
+
+    fn find(conn: &rusqlite::Connection, habit_id: &str) -> Result<Option<Habit>> {
+        let habit = conn
+            .query_row(
+                &format!("SELECT {} FROM habits WHERE id = ?1", HABIT_COLUMNS),
+                params![habit_id],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(habit)
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<Habit> {
+        let frequency_json: String = row.get(4)?;
+        let frequency = serde_json::from_str(&frequency_json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(4, rusqlite::types::Type::Text, Box::new(e)))?;
+
+        Ok(Habit {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            icon: row.get(2)?,
+            color: row.get(3)?,
+            frequency,
+            goal_count: row.get(5)?,
+            start_date: parse_date(row, 6)?,
+            is_archived: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+            version: row.get(10)?,
+        })
+    }
+}
+
+fn parse_date(row: &rusqlite::Row, index: usize) -> rusqlite::Result<NaiveDate> {
+    let value: String = row.get(index)?;
+    value
+        .parse()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
@@ -1,17 +1,29 @@
-use crate::db::Database;
+use crate::db::{Database, TaskRepository};
 use crate::error::{AppError, Result};
-use crate::models::List;
-use rusqlite::params;
+use crate::models::{DuplicateOptions, List, INBOX_LIST_ID};
+use crate::ordering;
+use rusqlite::{params, OptionalExtension};
+
+const LIST_COLUMNS: &str =
+    "id, name, icon, color, is_smart, order_num, is_archived, is_deleted, is_hidden, created_at, version, sort_key";
 
 pub struct ListRepository;
 
 impl ListRepository {
     pub fn create(db: &Database, list: &List) -> Result<List> {
         let conn = db.conn.lock().unwrap();
-        
+        Self::insert(&conn, list)
+    }
+
+    /// 插入清单，新清单排在最后
+    pub(crate) fn insert(conn: &rusqlite::Connection, list: &List) -> Result<List> {
+        let last_key: Option<String> = conn.query_row("SELECT MAX(sort_key) FROM lists", [], |row| row.get(0))?;
+        let sort_key = ordering::key_between(last_key.as_deref().filter(|k| !k.is_empty()), None)?;
+
         conn.execute(
-            "INSERT INTO lists (id, name, icon, color, is_smart, order_num, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO lists (id, name, icon, color, is_smart, order_num, is_archived, is_deleted, is_hidden, created_at,
+             version, sort_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, 1, ?11)",
             params![
                 list.id,
                 list.name,
@@ -19,89 +31,294 @@ impl ListRepository {
                 list.color,
                 list.is_smart as i32,
                 list.order,
+                list.is_archived as i32,
+                list.is_deleted as i32,
+                list.is_hidden as i32,
                 list.created_at,
+                sort_key,
             ],
         )?;
 
-        Ok(list.clone())
+        Ok(List { version: 1, sort_key, ..list.clone() })
     }
 
     pub fn get_by_id(db: &Database, list_id: &str) -> Result<List> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, name, icon, color, is_smart, order_num, created_at
-             FROM lists WHERE id = ?1"
-        )?;
 
-        let list = stmt.query_row(params![list_id], |row| {
-            Ok(List {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                icon: row.get(2)?,
-                color: row.get(3)?,
-                is_smart: row.get::<_, i32>(4)? != 0,
-                order: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })?;
-
-        Ok(list)
+        Self::find(&conn, list_id)?.ok_or_else(|| AppError::NotFound(format!("List {} not found", list_id)))
     }
 
-    pub fn get_all(db: &Database) -> Result<Vec<List>> {
+    pub fn get_all(db: &Database, include_archived: bool, include_hidden: bool) -> Result<Vec<List>> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, name, icon, color, is_smart, order_num, created_at
-             FROM lists ORDER BY order_num ASC, created_at ASC"
-        )?;
 
-        let lists_iter = stmt.query_map([], |row| {
-            Ok(List {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                icon: row.get(2)?,
-                color: row.get(3)?,
-                is_smart: row.get::<_, i32>(4)? != 0,
-                order: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM lists
+             WHERE is_deleted = 0 AND (?1 = 1 OR is_archived = 0) AND (?2 = 1 OR is_hidden = 0) AND id != ?3
+             ORDER BY sort_key ASC, created_at ASC",
+            LIST_COLUMNS
+        ))?;
+
+        // 收集箱清单通过智能清单「收集箱」展示，不重复出现在清单列表中
+        let lists_iter = stmt.query_map(
+            params![include_archived as i32, include_hidden as i32, INBOX_LIST_ID],
+            Self::map_row,
+        )?;
 
         let mut lists = Vec::new();
         for list in lists_iter {
             lists.push(list?);
         }
 
-        println!("Rust: ListRepository::get_all found {} lists", lists.len());
-        for l in &lists {
-            println!("  - ID: {}, Name: {}, IsSmart: {}", l.id, l.name, l.is_smart);
-        }
+        tracing::trace!(count = lists.len(), "loaded lists");
 
         Ok(lists)
     }
 
+    /// 更新清单。`list.version` 必须与数据库中的版本一致，否则返回冲突
     pub fn update(db: &Database, list: &List) -> Result<List> {
         let conn = db.conn.lock().unwrap();
-        
-        conn.execute(
-            "UPDATE lists SET name = ?1, icon = ?2, color = ?3, order_num = ?4 WHERE id = ?5",
-            params![list.name, list.icon, list.color, list.order, list.id],
+
+        let rows_affected = conn.execute(
+            "UPDATE lists SET name = ?1, icon = ?2, color = ?3, order_num = ?4, version = version + 1
+             WHERE id = ?5 AND version = ?6 AND is_deleted = 0",
+            params![list.name, list.icon, list.color, list.order, list.id, list.version],
         )?;
 
-        Ok(list.clone())
+        if rows_affected == 0 {
+            return Err(match Self::find(&conn, &list.id)? {
+                Some(current) => AppError::conflict(
+                    format!("List {} was modified (current version {})", list.id, current.version),
+                    &current,
+                ),
+                None => AppError::NotFound(format!("List {} not found", list.id)),
+            });
+        }
+
+        Self::find(&conn, &list.id)?.ok_or_else(|| AppError::NotFound(format!("List {} not found", list.id)))
     }
 
-    pub fn delete(db: &Database, list_id: &str) -> Result<()> {
+    /// 以 `name` 为名复制清单及其中全部未删除的任务树，新清单紧跟在原清单之后
+    pub fn duplicate(db: &Database, list_id: &str, name: &str, options: &DuplicateOptions) -> Result<List> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let original = Self::find(&tx, list_id)?
+            .ok_or_else(|| AppError::NotFound(format!("List {} not found", list_id)))?;
+        let next_key: Option<String> = tx.query_row(
+            "SELECT MIN(sort_key) FROM lists WHERE sort_key > ?1",
+            params![original.sort_key],
+            |row| row.get(0),
+        )?;
+
+        let copy = List {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            is_archived: false,
+            is_hidden: false,
+            created_at: chrono::Utc::now().timestamp(),
+            version: 1,
+            sort_key: ordering::key_between(Some(&original.sort_key), next_key.as_deref())?,
+            ..original
+        };
+        tx.execute(
+            "INSERT INTO lists (id, name, icon, color, is_smart, order_num, created_at, version, sort_key)
+             VALUES (?1, ?2, ?3, ?4, 0, ?5, ?6, 1, ?7)",
+            params![copy.id, copy.name, copy.icon, copy.color, copy.order, copy.created_at, copy.sort_key],
+        )?;
+        let copied = TaskRepository::copy_list_tasks(&tx, list_id, &copy.id, options)?;
+        tx.commit()?;
+
+        tracing::debug!(copied, "list duplicated");
+
+        Ok(copy)
+    }
+
+    /// 把清单放到 `prev_id` 与 `next_id` 之间，只写入被移动的清单
+    pub fn reorder(db: &Database, list_id: &str, prev_id: Option<&str>, next_id: Option<&str>) -> Result<List> {
         let conn = db.conn.lock().unwrap();
-        
-        let rows_affected = conn.execute("DELETE FROM lists WHERE id = ?1", params![list_id])?;
-        
+
+        let key_of = |id: Option<&str>| -> Result<Option<String>> {
+            id.map(|id| {
+                Self::find(&conn, id)?
+                    .map(|list| list.sort_key)
+                    .ok_or_else(|| AppError::NotFound(format!("List {} not found", id)))
+            })
+            .transpose()
+        };
+        let prev = key_of(prev_id)?;
+        let next = key_of(next_id)?;
+        if let (Some(prev), Some(next)) = (&prev, &next) {
+            if prev >= next {
+                return Err(AppError::InvalidInput(
+                    "Neighbouring lists are not in sort order; reload and try again".to_string(),
+                ));
+            }
+        }
+        let sort_key = ordering::key_between(prev.as_deref(), next.as_deref())?;
+
+        let rows_affected = conn.execute(
+            "UPDATE lists SET sort_key = ?1, version = version + 1 WHERE id = ?2 AND is_deleted = 0",
+            params![sort_key, list_id],
+        )?;
         if rows_affected == 0 {
             return Err(AppError::NotFound(format!("List {} not found", list_id)));
         }
 
+        Self::find(&conn, list_id)?.ok_or_else(|| AppError::NotFound(format!("List {} not found", list_id)))
+    }
+
+    /// 排序键过长或重复时重新均匀分配全部清单的排序键，返回更新的清单数
+    pub fn rebalance_sort_keys(db: &Database) -> Result<usize> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let needs_rebalance: bool = tx.query_row(
+            "SELECT MAX(length(sort_key)) > ?1 OR COUNT(*) > COUNT(DISTINCT sort_key) FROM lists",
+            params![ordering::MAX_KEY_LEN],
+            |row| Ok(row.get::<_, Option<bool>>(0)?.unwrap_or(false)),
+        )?;
+        if !needs_rebalance {
+            return Ok(0);
+        }
+
+        let ids = tx
+            .prepare("SELECT id FROM lists ORDER BY sort_key ASC, created_at ASC")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare("UPDATE lists SET sort_key = ?1 WHERE id = ?2")?;
+            for (id, key) in ids.iter().zip(ordering::spread_keys(ids.len())) {
+                updated += stmt.execute(params![key, id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// 设置智能清单的自定义名称，空字符串表示恢复为当前语言的默认名称
+    pub fn set_name(db: &Database, list_id: &str, name: &str) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::ensure_exists(&conn, list_id)?;
+        conn.execute(
+            "UPDATE lists SET name = ?1, version = version + 1 WHERE id = ?2",
+            params![name, list_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_hidden(db: &Database, list_id: &str, hidden: bool) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::ensure_exists(&conn, list_id)?;
+        conn.execute(
+            "UPDATE lists SET is_hidden = ?1, version = version + 1 WHERE id = ?2",
+            params![hidden as i32, list_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// 归档 / 取消归档清单：归档后清单及其任务在默认视图中隐藏，但数据完整保留
+    pub fn set_archived(db: &Database, list_id: &str, archived: bool) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::ensure_exists(&conn, list_id)?;
+        conn.execute(
+            "UPDATE lists SET is_archived = ?1, version = version + 1 WHERE id = ?2",
+            params![archived as i32, list_id],
+        )?;
+
+        Ok(())
+    }
+
+    /// 软删除清单。
+    ///
+    /// - `move_tasks_to` 为 `None` 时，清单内的任务全部移入垃圾桶，可随时恢复；
+    /// - 为 `Some(target)` 时，任务先整体迁移到目标清单，再删除清单本身。
+    ///
+    /// 清单行在垃圾桶清空且不再被任何任务引用后才会被物理删除。
+    pub fn delete(db: &Database, list_id: &str, move_tasks_to: Option<&str>) -> Result<()> {
+        let mut conn = db.conn.lock().unwrap();
+
+        Self::ensure_exists(&conn, list_id)?;
+
+        let now = chrono::Utc::now().timestamp();
+        let tx = conn.transaction()?;
+
+        match move_tasks_to {
+            Some(target_id) => {
+                if target_id == list_id {
+                    return Err(AppError::InvalidInput(
+                        "Cannot move tasks into the list being deleted".to_string(),
+                    ));
+                }
+                Self::ensure_exists(&tx, target_id)?;
+
+                // 垃圾桶中的任务也一并迁移，保证被删除的清单不再被引用；分组不随之迁移
+                tx.execute(
+                    "UPDATE tasks SET list_id = ?1, section_id = NULL, updated_at = ?2, version = version + 1
+                     WHERE list_id = ?3",
+                    params![target_id, now, list_id],
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "UPDATE tasks SET is_deleted = 1, deleted_at = ?1, updated_at = ?1, version = version + 1
+                     WHERE list_id = ?2 AND is_deleted = 0",
+                    params![now, list_id],
+                )?;
+            }
+        }
+
+        tx.execute(
+            "UPDATE lists SET is_deleted = 1, version = version + 1 WHERE id = ?1",
+            params![list_id],
+        )?;
+        tx.commit()?;
+
         Ok(())
     }
+
+    fn find(conn: &rusqlite::Connection, list_id: &str) -> Result<Option<List>> {
+        let list = conn
+            .query_row(
+                &format!("SELECT {} FROM lists WHERE id = ?1 AND is_deleted = 0", LIST_COLUMNS),
+                params![list_id],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(list)
+    }
+
+    fn ensure_exists(conn: &rusqlite::Connection, list_id: &str) -> Result<()> {
+        conn.query_row(
+            "SELECT 1 FROM lists WHERE id = ?1 AND is_deleted = 0",
+            params![list_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("List {} not found", list_id)))
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<List> {
+        Ok(List {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            icon: row.get(2)?,
+            color: row.get(3)?,
+            is_smart: row.get::<_, i32>(4)? != 0,
+            order: row.get(5)?,
+            is_archived: row.get::<_, i32>(6)? != 0,
+            is_deleted: row.get::<_, i32>(7)? != 0,
+            is_hidden: row.get::<_, i32>(8)? != 0,
+            created_at: row.get(9)?,
+            version: row.get(10)?,
+            sort_key: row.get(11)?,
+        })
+    }
 }
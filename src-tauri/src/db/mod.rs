@@ -2,8 +2,22 @@ pub mod connection;
 pub mod task_repo;
 pub mod list_repo;
 pub mod tag_repo;
+pub mod settings_repo;
+pub mod template_repo;
+pub mod focus_repo;
+pub mod time_entry_repo;
+pub mod stats_repo;
+pub mod habit_repo;
+pub mod section_repo;
 
 pub use connection::Database;
 pub use task_repo::TaskRepository;
 pub use list_repo::ListRepository;
 pub use tag_repo::TagRepository;
+pub use settings_repo::SettingsRepository;
+pub use template_repo::TemplateRepository;
+pub use focus_repo::FocusRepository;
+pub use time_entry_repo::TimeEntryRepository;
+pub use stats_repo::StatsRepository;
+pub use habit_repo::HabitRepository;
+pub use section_repo::SectionRepository;
@@ -0,0 +1,218 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::Section;
+use crate::ordering;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
+
+const SECTION_COLUMNS: &str = "id, list_id, name, sort_key, created_at, updated_at, version";
+
+/// 清单内的看板分组
+pub struct SectionRepository;
+
+impl SectionRepository {
+    pub fn create(db: &Database, section: &Section) -> Result<Section> {
+        let conn = db.conn.lock().unwrap();
+
+        // 新分组排在清单的最后
+        let last_key: Option<String> = conn.query_row(
+            "SELECT MAX(sort_key) FROM sections WHERE list_id = ?1",
+            params![section.list_id],
+            |row| row.get(0),
+        )?;
+        let sort_key = ordering::key_between(last_key.as_deref().filter(|k| !k.is_empty()), None)?;
+
+        conn.execute(
+            "INSERT INTO sections (id, list_id, name, sort_key, created_at, updated_at, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1)",
+            params![
+                section.id,
+                section.list_id,
+                section.name,
+                sort_key,
+                section.created_at,
+                section.updated_at,
+            ],
+        )?;
+
+        Ok(Section { version: 1, sort_key, ..section.clone() })
+    }
+
+    pub fn get_by_id(db: &Database, section_id: &str) -> Result<Section> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::find(&conn, section_id)?.ok_or_else(|| AppError::NotFound(format!("Section {} not found", section_id)))
+    }
+
+    pub fn get_by_list(db: &Database, list_id: &str) -> Result<Vec<Section>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM sections WHERE list_id = ?1 ORDER BY sort_key ASC, created_at ASC",
+            SECTION_COLUMNS
+        ))?;
+        let sections = stmt
+            .query_map(params![list_id], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Section>>>()?;
+
+        Ok(sections)
+    }
+
+    pub fn rename(db: &Database, section_id: &str, name: &str) -> Result<Section> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute(
+            "UPDATE sections SET name = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+            params![name, chrono::Utc::now().timestamp(), section_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Section {} not found", section_id)));
+        }
+
+        Self::find(&conn, section_id)?.ok_or_else(|| AppError::NotFound(format!("Section {} not found", section_id)))
+    }
+
+    /// 把分组放到同一清单中 `prev_id` 与 `next_id` 之间
+    pub fn reorder(db: &Database, section_id: &str, prev_id: Option<&str>, next_id: Option<&str>) -> Result<Section> {
+        let conn = db.conn.lock().unwrap();
+
+        let section = Self::find(&conn, section_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Section {} not found", section_id)))?;
+        let key_of = |id: Option<&str>| -> Result<Option<String>> {
+            id.map(|id| match Self::find(&conn, id)? {
+                Some(neighbour) if neighbour.list_id == section.list_id => Ok(neighbour.sort_key),
+                Some(_) => Err(AppError::InvalidInput(format!("Section {} is in a different list", id))),
+                None => Err(AppError::NotFound(format!("Section {} not found", id))),
+            })
+            .transpose()
+        };
+        let prev = key_of(prev_id)?;
+        let next = key_of(next_id)?;
+        if let (Some(prev), Some(next)) = (&prev, &next) {
+            if prev >= next {
+                return Err(AppError::InvalidInput(
+                    "Neighbouring sections are not in sort order; reload and try again".to_string(),
+                ));
+            }
+        }
+        let sort_key = ordering::key_between(prev.as_deref(), next.as_deref())?;
+
+        conn.execute(
+            "UPDATE sections SET sort_key = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+            params![sort_key, chrono::Utc::now().timestamp(), section_id],
+        )?;
+
+        Self::find(&conn, section_id)?.ok_or_else(|| AppError::NotFound(format!("Section {} not found", section_id)))
+    }
+
+    /// 删除分组。指定 `move_tasks_to` 时任务移入同一清单的该分组，否则成为未分组的任务
+    pub fn delete(db: &Database, section_id: &str, move_tasks_to: Option<&str>) -> Result<()> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let section = Self::find(&tx, section_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Section {} not found", section_id)))?;
+        if let Some(target_id) = move_tasks_to {
+            if target_id == section_id {
+                return Err(AppError::InvalidInput(
+                    "Cannot move tasks into the section being deleted".to_string(),
+                ));
+            }
+            match Self::find(&tx, target_id)? {
+                Some(target) if target.list_id == section.list_id => {}
+                Some(_) => {
+                    return Err(AppError::InvalidInput(format!("Section {} is in a different list", target_id)));
+                }
+                None => return Err(AppError::NotFound(format!("Section {} not found", target_id))),
+            }
+        }
+
+        tx.execute(
+            "UPDATE tasks SET section_id = ?1, updated_at = ?2, version = version + 1 WHERE section_id = ?3",
+            params![move_tasks_to, chrono::Utc::now().timestamp(), section_id],
+        )?;
+        tx.execute("DELETE FROM sections WHERE id = ?1", params![section_id])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// 把 `from_list_id` 的分组复制到 `to_list_id`，返回原 ID 到新 ID 的映射
+    pub(crate) fn copy_to_list(
+        conn: &rusqlite::Connection,
+        from_list_id: &str,
+        to_list_id: &str,
+    ) -> Result<HashMap<String, String>> {
+        let sections = conn
+            .prepare(&format!("SELECT {} FROM sections WHERE list_id = ?1", SECTION_COLUMNS))?
+            .query_map(params![from_list_id], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Section>>>()?;
+
+        let now = chrono::Utc::now().timestamp();
+        let mut copies = HashMap::new();
+        for section in sections {
+            let new_id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO sections (id, list_id, name, sort_key, created_at, updated_at, version)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?5, 1)",
+                params![new_id, to_list_id, section.name, section.sort_key, now],
+            )?;
+            copies.insert(section.id, new_id);
+        }
+
+        Ok(copies)
+    }
+
+    /// 排序键过长或重复时在各清单内重新均匀分配，返回更新的分组数
+    pub fn rebalance_sort_keys(db: &Database) -> Result<usize> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let list_ids = tx
+            .prepare(
+                "SELECT list_id FROM sections GROUP BY list_id
+                 HAVING MAX(length(sort_key)) > ?1 OR COUNT(*) > COUNT(DISTINCT sort_key)",
+            )?
+            .query_map(params![ordering::MAX_KEY_LEN], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut updated = 0;
+        for list_id in list_ids {
+            let ids = tx
+                .prepare("SELECT id FROM sections WHERE list_id = ?1 ORDER BY sort_key ASC, created_at ASC")?
+                .query_map(params![list_id], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+
+            let mut stmt = tx.prepare("UPDATE sections SET sort_key = ?1 WHERE id = ?2")?;
+            for (id, key) in ids.iter().zip(ordering::spread_keys(ids.len())) {
+                updated += stmt.execute(params![key, id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    fn find(conn: &rusqlite::Connection, section_id: &str) -> Result<Option<Section>> {
+        let section = conn
+            .query_row(
+                &format!("SELECT {} FROM sections WHERE id = ?1", SECTION_COLUMNS),
+                params![section_id],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(section)
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<Section> {
+        Ok(Section {
+            id: row.get(0)?,
+            list_id: row.get(1)?,
+            name: row.get(2)?,
+            sort_key: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+            version: row.get(6)?,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::db::Database;
+use crate::error::Result;
+use rusqlite::{params, OptionalExtension};
+
+/// 应用设置的键值存储
+pub struct SettingsRepository;
+
+impl SettingsRepository {
+    pub fn get(db: &Database, key: &str) -> Result<Option<String>> {
+        let conn = db.conn.lock().unwrap();
+
+        let value = conn
+            .query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?;
+
+        Ok(value)
+    }
+
+    pub fn get_all(db: &Database) -> Result<Vec<(String, String)>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+        let entries = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+
+        Ok(entries)
+    }
+
+    pub fn set_many(db: &Database, entries: &[(String, String)]) -> Result<()> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            )?;
+            for (key, value) in entries {
+                stmt.execute(params![key, value])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn set(db: &Database, key: &str, value: &str) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::Priority;
+use rusqlite::params;
+use std::collections::HashMap;
+
+/// ?1 ~ ?2 内新建、完成或到期的未删除任务
+const STATS_SCOPE_FILTER: &str = "t.is_deleted = 0 AND (
+        t.created_at BETWEEN ?1 AND ?2 OR t.completed_at BETWEEN ?1 AND ?2 OR t.due_date BETWEEN ?1 AND ?2
+    )";
+
+/// 统计所需的任务字段
+#[derive(Debug, Clone)]
+pub struct TaskFact {
+    pub list_id: String,
+    /// 清单名称，未本地化
+    pub list_name: String,
+    pub priority: Priority,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+    pub due_date: Option<i64>,
+    /// `(tag_id, tag_name)`
+    pub tags: Vec<(String, String)>,
+}
+
+/// 效率统计的只读查询
+pub struct StatsRepository;
+
+impl StatsRepository {
+    pub fn task_facts(db: &Database, start: i64, end: i64) -> Result<Vec<TaskFact>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut tags: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT t.id, tg.id, tg.name FROM tasks t
+                 JOIN task_tags tt ON tt.task_id = t.id
+                 JOIN tags tg ON tg.id = tt.tag_id
+                 WHERE {}",
+                STATS_SCOPE_FILTER
+            ))?;
+            let rows = stmt.query_map(params![start, end], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+            })?;
+            for row in rows {
+                let (task_id, tag_id, tag_name) = row?;
+                tags.entry(task_id).or_default().push((tag_id, tag_name));
+            }
+        }
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT t.id, t.list_id, COALESCE(l.name, ''), t.priority, t.created_at, t.completed_at, t.due_date
+             FROM tasks t LEFT JOIN lists l ON l.id = t.list_id
+             WHERE {}",
+            STATS_SCOPE_FILTER
+        ))?;
+        let facts = stmt
+            .query_map(params![start, end], |row| {
+                let task_id: String = row.get(0)?;
+                Ok(TaskFact {
+                    list_id: row.get(1)?,
+                    list_name: row.get(2)?,
+                    priority: Priority::from_i32(row.get(3)?),
+                    created_at: row.get(4)?,
+                    completed_at: row.get(5)?,
+                    due_date: row.get(6)?,
+                    tags: tags.remove(&task_id).unwrap_or_default(),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<TaskFact>>>()?;
+
+        Ok(facts)
+    }
+
+    /// `until` 及之前全部未删除任务的完成时间，用于计算连续完成天数
+    pub fn completion_times(db: &Database, until: i64) -> Result<Vec<i64>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT completed_at FROM tasks WHERE is_deleted = 0 AND completed_at IS NOT NULL AND completed_at <= ?1",
+        )?;
+        let times = stmt
+            .query_map(params![until], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i64>>>()?;
+
+        Ok(times)
+    }
+}
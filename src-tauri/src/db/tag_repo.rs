@@ -1,78 +1,305 @@
 use crate::db::Database;
 use crate::error::{AppError, Result};
-use crate::models::Tag;
-use rusqlite::params;
+use crate::models::{Tag, TagWithStats};
+use crate::ordering;
+use rusqlite::{params, OptionalExtension};
+
+const TAG_COLUMNS: &str = "id, name, color, parent_id, is_pinned, created_at, version, sort_key";
+
+/// 置顶标签在前，其余按用户自定义顺序
+const TAG_ORDER: &str = "is_pinned DESC, sort_key ASC, name ASC";
+
+/// 按标签汇总的使用统计：未删除任务的未完成 / 已完成数量与最近打标签时间
+const TAG_STATS_SQL: &str = "SELECT tt.tag_id,
+        COALESCE(SUM(t.completed = 0 AND t.is_deleted = 0), 0) AS open_count,
+        COALESCE(SUM(t.completed = 1 AND t.is_deleted = 0), 0) AS completed_count,
+        MAX(tt.tagged_at) AS last_used_at
+    FROM task_tags tt JOIN tasks t ON t.id = tt.task_id
+    GROUP BY tt.tag_id";
+
+/// 以 ?1 为根的标签子树（含根本身）
+pub(crate) const TAG_SUBTREE_SQL: &str = "WITH RECURSIVE subtree(id) AS (
+        SELECT ?1
+        UNION
+        SELECT t.id FROM tags t JOIN subtree s ON t.parent_id = s.id
+    )
+    SELECT id FROM subtree";
 
 pub struct TagRepository;
 
 impl TagRepository {
     pub fn create(db: &Database, tag: &Tag) -> Result<Tag> {
         let conn = db.conn.lock().unwrap();
-        
+        Self::insert(&conn, tag)
+    }
+
+    /// 插入标签，新标签排在最后
+    pub(crate) fn insert(conn: &rusqlite::Connection, tag: &Tag) -> Result<Tag> {
+        let last_key: Option<String> = conn.query_row("SELECT MAX(sort_key) FROM tags", [], |row| row.get(0))?;
+        let sort_key = ordering::key_between(last_key.as_deref().filter(|k| !k.is_empty()), None)?;
+
         conn.execute(
-            "INSERT INTO tags (id, name, color, parent_id, is_pinned, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![tag.id, tag.name, tag.color, tag.parent_id, if tag.is_pinned { 1 } else { 0 }, tag.created_at],
+            "INSERT INTO tags (id, name, name_key, color, parent_id, is_pinned, created_at, version, sort_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8)",
+            params![
+                tag.id,
+                tag.name,
+                Tag::name_key(&tag.name),
+                tag.color,
+                tag.parent_id,
+                if tag.is_pinned { 1 } else { 0 },
+                tag.created_at,
+                sort_key,
+            ],
         )?;
 
-        Ok(tag.clone())
+        Ok(Tag { version: 1, sort_key, ..tag.clone() })
     }
 
     pub fn get_by_id(db: &Database, tag_id: &str) -> Result<Tag> {
         let conn = db.conn.lock().unwrap();
         
-        let mut stmt = conn.prepare(
-            "SELECT id, name, color, parent_id, is_pinned, created_at FROM tags WHERE id = ?1"
-        )?;
+        Self::find(&conn, tag_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Tag {} not found", tag_id)))
+    }
 
-        let tag = stmt.query_row(params![tag_id], |row| {
-            Ok(Tag {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-                parent_id: row.get(3)?,
-                is_pinned: row.get::<_, i32>(4)? != 0,
-                created_at: row.get(5)?,
-            })
-        })?;
+    /// 按规范化名称查找标签，大小写与全角 / 半角差异视为同名
+    pub fn find_by_name(db: &Database, name: &str) -> Result<Option<Tag>> {
+        let conn = db.conn.lock().unwrap();
 
+        let tag = conn
+            .query_row(
+                &format!("SELECT {} FROM tags WHERE name_key = ?1", TAG_COLUMNS),
+                params![Tag::name_key(name)],
+                Self::map_row,
+            )
+            .optional()?;
         Ok(tag)
     }
 
     pub fn get_all(db: &Database) -> Result<Vec<Tag>> {
         let conn = db.conn.lock().unwrap();
         
-        let mut stmt = conn.prepare(
-            "SELECT id, name, color, parent_id, is_pinned, created_at FROM tags ORDER BY name ASC"
-        )?;
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM tags ORDER BY {}", TAG_COLUMNS, TAG_ORDER))?;
 
-        let tags = stmt.query_map([], |row| {
-            Ok(Tag {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                color: row.get(2)?,
-                parent_id: row.get(3)?,
-                is_pinned: row.get::<_, i32>(4)? != 0,
-                created_at: row.get(5)?,
-            })
-        })?
-        .collect::<rusqlite::Result<Vec<Tag>>>()?;
+        let tags = stmt
+            .query_map([], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Tag>>>()?;
+
+        Ok(tags)
+    }
+
+    /// 全部标签及其使用统计，排序与 [`Self::get_all`] 一致
+    pub fn get_all_with_stats(db: &Database) -> Result<Vec<TagWithStats>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, COALESCE(s.open_count, 0), COALESCE(s.completed_count, 0), s.last_used_at
+             FROM tags LEFT JOIN ({}) s ON s.tag_id = tags.id
+             ORDER BY {}",
+            TAG_COLUMNS, TAG_STATS_SQL, TAG_ORDER
+        ))?;
+
+        let tags = stmt
+            .query_map([], Self::map_stats_row)?
+            .collect::<rusqlite::Result<Vec<TagWithStats>>>()?;
+
+        Ok(tags)
+    }
+
+    /// 最近使用过的标签，按最近打标签时间倒序
+    pub fn get_recent(db: &Database, limit: usize) -> Result<Vec<TagWithStats>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, s.open_count, s.completed_count, s.last_used_at
+             FROM tags JOIN ({}) s ON s.tag_id = tags.id
+             WHERE s.last_used_at IS NOT NULL
+             ORDER BY s.last_used_at DESC, name ASC
+             LIMIT ?1",
+            TAG_COLUMNS, TAG_STATS_SQL
+        ))?;
+
+        let tags = stmt
+            .query_map(params![limit as i64], Self::map_stats_row)?
+            .collect::<rusqlite::Result<Vec<TagWithStats>>>()?;
 
         Ok(tags)
     }
 
+    /// 更新标签。`tag.version` 必须与数据库中的版本一致，否则返回冲突
     pub fn update(db: &Database, tag: &Tag) -> Result<Tag> {
         let conn = db.conn.lock().unwrap();
         
         let rows_affected = conn.execute(
-            "UPDATE tags SET name = ?1, color = ?2, parent_id = ?3, is_pinned = ?4 WHERE id = ?5",
-            params![tag.name, tag.color, tag.parent_id, if tag.is_pinned { 1 } else { 0 }, tag.id],
+            "UPDATE tags SET name = ?1, name_key = ?2, color = ?3, parent_id = ?4, is_pinned = ?5, version = version + 1
+             WHERE id = ?6 AND version = ?7",
+            params![
+                tag.name,
+                Tag::name_key(&tag.name),
+                tag.color,
+                tag.parent_id,
+                if tag.is_pinned { 1 } else { 0 },
+                tag.id,
+                tag.version,
+            ],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(match Self::find(&conn, &tag.id)? {
+                Some(current) => AppError::conflict(
+                    format!("Tag {} was modified (current version {})", tag.id, current.version),
+                    &current,
+                ),
+                None => AppError::NotFound(format!("Tag {} not found", tag.id)),
+            });
+        }
+
+        // 排序键不随更新写入，返回数据库中的最新值
+        Self::find(&conn, &tag.id)?.ok_or_else(|| AppError::NotFound(format!("Tag {} not found", tag.id)))
+    }
+
+    /// 把标签放到 `prev_id` 与 `next_id` 之间，只写入被移动的标签
+    pub fn reorder(db: &Database, tag_id: &str, prev_id: Option<&str>, next_id: Option<&str>) -> Result<Tag> {
+        let conn = db.conn.lock().unwrap();
+
+        let key_of = |id: Option<&str>| -> Result<Option<String>> {
+            id.map(|id| {
+                Self::find(&conn, id)?
+                    .map(|tag| tag.sort_key)
+                    .ok_or_else(|| AppError::NotFound(format!("Tag {} not found", id)))
+            })
+            .transpose()
+        };
+        let prev = key_of(prev_id)?;
+        let next = key_of(next_id)?;
+        if let (Some(prev), Some(next)) = (&prev, &next) {
+            if prev >= next {
+                return Err(AppError::InvalidInput(
+                    "Neighbouring tags are not in sort order; reload and try again".to_string(),
+                ));
+            }
+        }
+        let sort_key = ordering::key_between(prev.as_deref(), next.as_deref())?;
+
+        let rows_affected = conn.execute(
+            "UPDATE tags SET sort_key = ?1, version = version + 1 WHERE id = ?2",
+            params![sort_key, tag_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Tag {} not found", tag_id)));
+        }
+
+        Self::find(&conn, tag_id)?.ok_or_else(|| AppError::NotFound(format!("Tag {} not found", tag_id)))
+    }
+
+    /// 排序键过长或重复时重新均匀分配全部标签的排序键，返回更新的标签数
+    pub fn rebalance_sort_keys(db: &Database) -> Result<usize> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let needs_rebalance: bool = tx.query_row(
+            "SELECT MAX(length(sort_key)) > ?1 OR COUNT(*) > COUNT(DISTINCT sort_key) FROM tags",
+            params![ordering::MAX_KEY_LEN],
+            |row| Ok(row.get::<_, Option<bool>>(0)?.unwrap_or(false)),
         )?;
+        if !needs_rebalance {
+            return Ok(0);
+        }
+
+        let ids = tx
+            .prepare("SELECT id FROM tags ORDER BY sort_key ASC, name ASC")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare("UPDATE tags SET sort_key = ?1 WHERE id = ?2")?;
+            for (id, key) in ids.iter().zip(ordering::spread_keys(ids.len())) {
+                updated += stmt.execute(params![key, id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(updated)
+    }
 
+    /// 修改父标签，`parent_id` 为 `None` 时成为根标签
+    pub fn set_parent(db: &Database, tag_id: &str, parent_id: Option<&str>) -> Result<Tag> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute(
+            "UPDATE tags SET parent_id = ?1, version = version + 1 WHERE id = ?2",
+            params![parent_id, tag_id],
+        )?;
         if rows_affected == 0 {
-            return Err(AppError::NotFound(format!("Tag {} not found", tag.id)));
+            return Err(AppError::NotFound(format!("Tag {} not found", tag_id)));
+        }
+
+        Self::find(&conn, tag_id)?.ok_or_else(|| AppError::NotFound(format!("Tag {} not found", tag_id)))
+    }
+
+    /// `tag_id` 是否位于 `ancestor_id` 的子树中（含自身）
+    pub fn is_in_subtree(db: &Database, ancestor_id: &str, tag_id: &str) -> Result<bool> {
+        let conn = db.conn.lock().unwrap();
+
+        let found = conn
+            .query_row(
+                &format!("SELECT 1 FROM ({}) WHERE id = ?2", TAG_SUBTREE_SQL),
+                params![ancestor_id, tag_id],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// 把 `source_id` 合并到 `target_id`：任务关联与子标签转移到目标标签后删除源标签。
+    ///
+    /// 调用方需保证目标标签不在源标签的子树中。
+    pub fn merge(db: &Database, source_id: &str, target_id: &str) -> Result<Tag> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if Self::find(&tx, source_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Tag {} not found", source_id)));
         }
+        if Self::find(&tx, target_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Tag {} not found", target_id)));
+        }
+
+        let retagged = Self::merge_into(&tx, source_id, target_id)?;
+
+        let target = Self::find(&tx, target_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Tag {} not found", target_id)))?;
+        tx.commit()?;
+
+        tracing::debug!(retagged, "tags merged");
+
+        Ok(target)
+    }
+
+    /// 在已有连接上执行合并，返回涉及的任务数；启动时合并重名标签也使用此函数
+    pub(crate) fn merge_into(conn: &rusqlite::Connection, source_id: &str, target_id: &str) -> rusqlite::Result<usize> {
+        let now = chrono::Utc::now().timestamp();
+        let retagged = conn.execute(
+            "UPDATE tasks SET updated_at = ?1, version = version + 1
+             WHERE id IN (SELECT task_id FROM task_tags WHERE tag_id = ?2)",
+            params![now, source_id],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO task_tags (task_id, tag_id, tagged_at)
+             SELECT task_id, ?2, tagged_at FROM task_tags WHERE tag_id = ?1",
+            params![source_id, target_id],
+        )?;
+        conn.execute("DELETE FROM task_tags WHERE tag_id = ?1", params![source_id])?;
+        conn.execute(
+            "UPDATE tags SET parent_id = ?2, version = version + 1 WHERE parent_id = ?1",
+            params![source_id, target_id],
+        )?;
+        conn.execute("DELETE FROM tags WHERE id = ?1", params![source_id])?;
+        conn.execute("UPDATE tags SET version = version + 1 WHERE id = ?1", params![target_id])?;
 
-        Ok(tag.clone())
+        Ok(retagged)
     }
 
     pub fn delete(db: &Database, tag_id: &str) -> Result<()> {
@@ -89,4 +316,37 @@ impl TagRepository {
 
         Ok(())
     }
+
+    fn find(conn: &rusqlite::Connection, tag_id: &str) -> Result<Option<Tag>> {
+        let tag = conn
+            .query_row(
+                &format!("SELECT {} FROM tags WHERE id = ?1", TAG_COLUMNS),
+                params![tag_id],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(tag)
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<Tag> {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            color: row.get(2)?,
+            parent_id: row.get(3)?,
+            is_pinned: row.get::<_, i32>(4)? != 0,
+            created_at: row.get(5)?,
+            version: row.get(6)?,
+            sort_key: row.get(7)?,
+        })
+    }
+
+    fn map_stats_row(row: &rusqlite::Row) -> rusqlite::Result<TagWithStats> {
+        Ok(TagWithStats {
+            tag: Self::map_row(row)?,
+            open_count: row.get(8)?,
+            completed_count: row.get(9)?,
+            last_used_at: row.get(10)?,
+        })
+    }
 }
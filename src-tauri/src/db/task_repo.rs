@@ -1,243 +1,222 @@
-use crate::db::Database;
+use crate::db::tag_repo::TAG_SUBTREE_SQL;
+use crate::db::{Database, ListRepository, SectionRepository, TagRepository};
 use crate::error::{AppError, Result};
-use crate::models::{Priority, Task};
-use rusqlite::params;
+use crate::models::{
+    BulkItemResult, BulkTaskOperation, DuplicateOptions, List, Priority, Settings, SmartListType, Tag, Task, TaskPatch,
+    INBOX_LIST_ID,
+};
+use crate::ordering;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, OptionalExtension};
+use std::collections::HashMap;
+
+const TASK_COLUMNS: &str = "id, title, description, list_id, completed, priority,
+    due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at, version, sort_key,
+    estimated_minutes, start_date, duration_minutes, is_all_day, section_id";
+
+/// 智能清单只展示未归档、未删除清单中的任务
+const VISIBLE_LIST_FILTER: &str =
+    "list_id NOT IN (SELECT id FROM lists WHERE is_archived = 1 OR is_deleted = 1)";
+
+/// 未完成且已过截止期限的任务，?1 为今天零点、?2 为当前时间。
+///
+/// 落在零点的截止日期表示「当天内」，期限为当天结束：今天零点的还没逾期，更早的零点都已逾期，
+/// 与 `Settings::due_deadline` 一致。
+const OVERDUE_FILTER: &str = "completed = 0 AND due_date < ?2 AND due_date != ?1";
+
+/// 以 ?1 为根的所有后代任务 ID（不含根本身）
+const DESCENDANTS_SQL: &str = "WITH RECURSIVE subtree(id) AS (
+        SELECT id FROM tasks WHERE parent_id = ?1
+        UNION
+        SELECT t.id FROM tasks t JOIN subtree s ON t.parent_id = s.id
+    )
+    SELECT id FROM subtree";
 
 pub struct TaskRepository;
 
 impl TaskRepository {
     pub fn create(db: &Database, task: &Task) -> Result<Task> {
         let conn = db.conn.lock().unwrap();
-        
-        let repeat_rule_json = task.repeat_rule.as_ref().map(|r| serde_json::to_string(r).unwrap());
 
-        conn.execute(
-            "INSERT INTO tasks (id, title, description, list_id, completed, priority, 
-             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
-            params![
-                task.id,
-                task.title,
-                task.description,
-                task.list_id,
-                task.completed as i32,
-                task.priority.to_i32(),
-                task.due_date,
-                task.reminder,
-                repeat_rule_json,
-                task.parent_id,
-                task.order,
-                task.is_deleted as i32,
-                task.created_at,
-                task.updated_at,
-                task.completed_at,
-            ],
+        // 新任务排在同级任务的最前面
+        let first_key: Option<String> = conn.query_row(
+            "SELECT MIN(sort_key) FROM tasks WHERE list_id = ?1 AND parent_id IS ?2 AND sort_key != ''",
+            params![task.list_id, task.parent_id],
+            |row| row.get(0),
         )?;
-
-        // 插入标签关联
-        for tag_id in &task.tags {
-            conn.execute(
-                "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
-                params![task.id, tag_id],
-            )?;
-        }
+        let task = Task { sort_key: ordering::key_between(None, first_key.as_deref())?, ..task.clone() };
+        Self::insert(&conn, &task)?;
 
         // 重新加载标签，确保返回的 Task 包含真实有效的标签 ID
         let tags = Self::get_task_tags(&conn, &task.id)?;
-        Ok(Task { tags, ..task.clone() })
+        Ok(Task { tags, version: 1, ..task })
     }
 
     pub fn get_by_id(db: &Database, task_id: &str) -> Result<Task> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, title, description, list_id, completed, priority, 
-             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-             FROM tasks WHERE id = ?1"
-        )?;
-
-        let task = stmt.query_row(params![task_id], Self::map_row)?;
 
-        // 加载标签
-        let tags = Self::get_task_tags(&conn, task_id)?;
-        Ok(Task { tags, ..task })
+        Self::find(&conn, task_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))
     }
 
     pub fn get_all(db: &Database) -> Result<Vec<Task>> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, title, description, list_id, completed, priority, 
-             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-             FROM tasks WHERE is_deleted = 0 ORDER BY order_num ASC, created_at DESC"
-        )?;
 
-        let tasks = stmt.query_map([], Self::map_row)?
-            .collect::<rusqlite::Result<Vec<Task>>>()?;
-
-        // 为每个任务加载标签
-        let mut tasks_with_tags = Vec::new();
-        for task in tasks {
-            let tags = Self::get_task_tags(&conn, &task.id)?;
-            tasks_with_tags.push(Task { tags, ..task });
-        }
-
-        Ok(tasks_with_tags)
+        Self::query_tasks(
+            &conn,
+            &format!(
+                "SELECT {} FROM tasks WHERE is_deleted = 0 AND {} ORDER BY sort_key ASC, created_at DESC",
+                TASK_COLUMNS, VISIBLE_LIST_FILTER
+            ),
+            [],
+        )
     }
 
-    pub fn get_by_list(db: &Database, list_id: &str) -> Result<Vec<Task>> {
+    pub fn get_by_list(db: &Database, list_id: &str, settings: &Settings) -> Result<Vec<Task>> {
         let conn = db.conn.lock().unwrap();
-        
-        let (query, has_params) = match list_id {
-            "smart_trash" => (
-                "SELECT id, title, description, list_id, completed, priority, 
-                 due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                 FROM tasks WHERE is_deleted = 1 ORDER BY updated_at DESC",
-                false
+
+        // 「今天」与「最近7天」按用户时区划分日期
+        let today = settings.today();
+        let (today_start, _) = settings.day_bounds(today);
+
+        match SmartListType::from_id(list_id) {
+            Some(SmartListType::Trash) => Self::query_tasks(
+                &conn,
+                &format!("SELECT {} FROM tasks WHERE is_deleted = 1 ORDER BY updated_at DESC", TASK_COLUMNS),
+                [],
             ),
-            "smart_completed" => (
-                "SELECT id, title, description, list_id, completed, priority, 
-                 due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                 FROM tasks WHERE completed = 1 AND is_deleted = 0 ORDER BY completed_at DESC, updated_at DESC",
-                false
+            Some(SmartListType::Completed) => Self::query_tasks(
+                &conn,
+                &format!(
+                    "SELECT {} FROM tasks WHERE completed = 1 AND is_deleted = 0 AND {}
+                     ORDER BY completed_at DESC, updated_at DESC",
+                    TASK_COLUMNS, VISIBLE_LIST_FILTER
+                ),
+                [],
             ),
-            "smart_all" => (
-                "SELECT id, title, description, list_id, completed, priority, 
-                 due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                 FROM tasks WHERE is_deleted = 0 ORDER BY order_num ASC, created_at DESC",
-                false
+            Some(SmartListType::All) => Self::query_tasks(
+                &conn,
+                &format!(
+                    "SELECT {} FROM tasks WHERE is_deleted = 0 AND {} ORDER BY sort_key ASC, created_at DESC",
+                    TASK_COLUMNS, VISIBLE_LIST_FILTER
+                ),
+                [],
             ),
-            "smart_today" => {
-                let now = chrono::Utc::now();
-                let today_end = now.date_naive().and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp();
-                let today_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
-                
-                let query = "SELECT id, title, description, list_id, completed, priority, 
-                             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                             FROM tasks 
-                             WHERE is_deleted = 0 AND (
-                                (completed = 0 AND due_date <= ?1) OR 
-                                (completed = 1 AND completed_at >= ?2 AND completed_at <= ?1)
-                             )
-                             ORDER BY completed ASC, due_date ASC, created_at DESC";
-                
-                let mut stmt = conn.prepare(query)?;
-                let tasks = stmt.query_map(params![today_end, today_start], Self::map_row)?
-                    .collect::<rusqlite::Result<Vec<Task>>>()?;
-                
-                let mut tasks_with_tags = Vec::new();
-                for task in tasks {
-                    let tags = Self::get_task_tags(&conn, &task.id)?;
-                    tasks_with_tags.push(Task { tags, ..task });
-                }
-                return Ok(tasks_with_tags);
-            },
-            "smart_week" => {
-                let now = chrono::Utc::now();
-                let week_end = (now + chrono::Duration::days(7)).date_naive().and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp();
-                let today_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp();
-
-                let query = "SELECT id, title, description, list_id, completed, priority, 
-                             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                             FROM tasks 
-                             WHERE is_deleted = 0 AND (
-                                (completed = 0 AND due_date <= ?1) OR 
-                                (completed = 1 AND completed_at >= ?2 AND completed_at <= ?1)
-                             )
-                             ORDER BY completed ASC, due_date ASC, created_at DESC";
-                
-                let mut stmt = conn.prepare(query)?;
-                let tasks = stmt.query_map(params![week_end, today_start], Self::map_row)?
-                    .collect::<rusqlite::Result<Vec<Task>>>()?;
-                
-                let mut tasks_with_tags = Vec::new();
-                for task in tasks {
-                    let tags = Self::get_task_tags(&conn, &task.id)?;
-                    tasks_with_tags.push(Task { tags, ..task });
-                }
-                return Ok(tasks_with_tags);
-            },
-            _ => (
-                "SELECT id, title, description, list_id, completed, priority, 
-                 due_date, reminder, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-                 FROM tasks WHERE list_id = ?1 AND is_deleted = 0 ORDER BY order_num ASC, created_at DESC",
-                true
+            Some(list_type @ (SmartListType::Today | SmartListType::Week)) => {
+                let days = if list_type == SmartListType::Today { 0 } else { 7 };
+                let (_, range_end) = settings.day_bounds(today + chrono::Duration::days(days));
+
+                // 已经开始但尚未到期的任务同样出现在视图中
+                Self::query_tasks(
+                    &conn,
+                    &format!(
+                        "SELECT {} FROM tasks
+                         WHERE is_deleted = 0 AND {} AND (
+                            (completed = 0 AND (due_date <= ?1 OR start_date <= ?1)) OR
+                            (completed = 1 AND completed_at >= ?2 AND completed_at <= ?1)
+                         )
+                         ORDER BY completed ASC, COALESCE(due_date, start_date) ASC, created_at DESC",
+                        TASK_COLUMNS, VISIBLE_LIST_FILTER
+                    ),
+                    params![range_end, today_start],
+                )
+            }
+            Some(SmartListType::Overdue) => Self::query_overdue(&conn, today_start, chrono::Utc::now().timestamp()),
+            // 收集箱即默认清单的视图
+            Some(SmartListType::Inbox) => Self::query_tasks(
+                &conn,
+                &format!(
+                    "SELECT {} FROM tasks WHERE list_id = ?1 AND is_deleted = 0 ORDER BY sort_key ASC, created_at DESC",
+                    TASK_COLUMNS
+                ),
+                params![INBOX_LIST_ID],
             ),
-        };
+            None => Self::query_tasks(
+                &conn,
+                &format!(
+                    "SELECT {} FROM tasks WHERE list_id = ?1 AND is_deleted = 0 ORDER BY sort_key ASC, created_at DESC",
+                    TASK_COLUMNS
+                ),
+                params![list_id],
+            ),
+        }
+    }
 
-        let mut stmt = conn.prepare(query)?;
+    /// 按标签查询任务；`include_children` 为 true 时同时匹配所有子孙标签
+    pub fn get_by_tag(db: &Database, tag_id: &str, include_children: bool) -> Result<Vec<Task>> {
+        let conn = db.conn.lock().unwrap();
 
-        let tasks = if has_params {
-            stmt.query_map(params![list_id], Self::map_row)?
-                .collect::<rusqlite::Result<Vec<Task>>>()?
+        let tag_filter = if include_children {
+            format!("tag_id IN ({})", TAG_SUBTREE_SQL)
         } else {
-            stmt.query_map([], Self::map_row)?
-                .collect::<rusqlite::Result<Vec<Task>>>()?
+            "tag_id = ?1".to_string()
         };
 
-        let mut tasks_with_tags = Vec::new();
-        for task in tasks {
-            let tags = Self::get_task_tags(&conn, &task.id)?;
-            tasks_with_tags.push(Task { tags, ..task });
-        }
-
-        Ok(tasks_with_tags)
+        Self::query_tasks(
+            &conn,
+            &format!(
+                "SELECT {} FROM tasks
+                 WHERE id IN (SELECT task_id FROM task_tags WHERE {}) AND is_deleted = 0 AND {}
+                 ORDER BY sort_key ASC, created_at DESC",
+                TASK_COLUMNS, tag_filter, VISIBLE_LIST_FILTER
+            ),
+            params![tag_id],
+        )
     }
 
-    pub fn get_by_tag(db: &Database, tag_id: &str) -> Result<Vec<Task>> {
+    /// 开始时间（没有时为截止日期）在 `[start, end]` 内的任务（含已完成），以及更早开始、可能在范围内重复的任务
+    pub fn get_by_schedule_range(db: &Database, start: i64, end: i64) -> Result<Vec<Task>> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT t.id, t.title, t.description, t.list_id, t.completed, t.priority, 
-             t.due_date, t.reminder, t.repeat_rule, t.parent_id, t.order_num, t.is_deleted, t.created_at, t.updated_at, t.completed_at
-             FROM tasks t
-             INNER JOIN task_tags tt ON t.id = tt.task_id
-             WHERE tt.tag_id = ?1 AND t.is_deleted = 0
-             ORDER BY t.order_num ASC, t.created_at DESC"
-        )?;
 
-        let tasks = stmt.query_map(params![tag_id], Self::map_row)?
-            .collect::<rusqlite::Result<Vec<Task>>>()?;
+        Self::query_tasks(
+            &conn,
+            &format!(
+                "SELECT {} FROM tasks
+                 WHERE is_deleted = 0 AND {} AND (
+                    COALESCE(start_date, due_date) BETWEEN ?1 AND ?2 OR
+                    (repeat_rule IS NOT NULL AND COALESCE(start_date, due_date) < ?1)
+                 )
+                 ORDER BY COALESCE(start_date, due_date) ASC, sort_key ASC",
+                TASK_COLUMNS, VISIBLE_LIST_FILTER
+            ),
+            params![start, end],
+        )
+    }
 
-        let mut tasks_with_tags = Vec::new();
-        for task in tasks {
-            let tags = Self::get_task_tags(&conn, &task.id)?;
-            tasks_with_tags.push(Task { tags, ..task });
-        }
+    /// 当前逾期的任务，按截止日期升序
+    pub fn get_overdue(db: &Database, settings: &Settings, now: i64) -> Result<Vec<Task>> {
+        let conn = db.conn.lock().unwrap();
 
-        Ok(tasks_with_tags)
+        let (today_start, _) = settings.day_bounds(settings.today());
+        Self::query_overdue(&conn, today_start, now)
     }
 
     pub fn get_by_parent(db: &Database, parent_id: &str) -> Result<Vec<Task>> {
         let conn = db.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, title, description, list_id, completed, priority, 
-             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at
-             FROM tasks WHERE parent_id = ?1 AND is_deleted = 0 ORDER BY order_num ASC, created_at DESC"
-        )?;
-
-        let tasks = stmt.query_map(params![parent_id], Self::map_row)?
-            .collect::<rusqlite::Result<Vec<Task>>>()?;
-
-        let mut tasks_with_tags = Vec::new();
-        for task in tasks {
-            let tags = Self::get_task_tags(&conn, &task.id)?;
-            tasks_with_tags.push(Task { tags, ..task });
-        }
 
-        Ok(tasks_with_tags)
+        Self::query_tasks(
+            &conn,
+            &format!(
+                "SELECT {} FROM tasks WHERE parent_id = ?1 AND is_deleted = 0 ORDER BY sort_key ASC, created_at DESC",
+                TASK_COLUMNS
+            ),
+            params![parent_id],
+        )
     }
 
+    /// 全量更新任务。`task.version` 必须与数据库中的版本一致，否则返回冲突
     pub fn update(db: &Database, task: &Task) -> Result<Task> {
-        let conn = db.conn.lock().unwrap();
-        
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
         let repeat_rule_json = task.repeat_rule.as_ref().map(|r| serde_json::to_string(r).unwrap());
 
-        conn.execute(
+        let rows_affected = tx.execute(
             "UPDATE tasks SET title = ?1, description = ?2, list_id = ?3, completed = ?4, 
              priority = ?5, due_date = ?6, reminder = ?7, repeat_rule = ?8, parent_id = ?9, order_num = ?10, 
-             is_deleted = ?11, updated_at = ?12, completed_at = ?13 WHERE id = ?14",
+             is_deleted = ?11, updated_at = ?12, completed_at = ?13, estimated_minutes = ?14, start_date = ?15,
+             duration_minutes = ?16, is_all_day = ?17, section_id = ?18, version = version + 1
+             WHERE id = ?19 AND version = ?20",
             params![
                 task.title,
                 task.description,
@@ -252,22 +231,145 @@ impl TaskRepository {
                 task.is_deleted as i32,
                 task.updated_at,
                 task.completed_at,
+                task.estimated_minutes,
+                task.start_date,
+                task.duration_minutes,
+                task.is_all_day as i32,
+                task.section_id,
                 task.id,
+                task.version,
             ],
         )?;
 
-        // 更新标签关联
-        conn.execute("DELETE FROM task_tags WHERE task_id = ?1", params![task.id])?;
+        if rows_affected == 0 {
+            return Err(Self::stale_write_error(&tx, &task.id)?);
+        }
+
+        // 更新标签关联，保留的标签维持原来的打标签时间
+        for tag_id in Self::get_task_tags(&tx, &task.id)? {
+            if !task.tags.contains(&tag_id) {
+                Self::detach_tag(&tx, &task.id, &tag_id)?;
+            }
+        }
         for tag_id in &task.tags {
-            conn.execute(
-                "INSERT OR IGNORE INTO task_tags (task_id, tag_id) VALUES (?1, ?2)",
-                params![task.id, tag_id],
-            )?;
+            Self::attach_tag(&tx, &task.id, tag_id, task.updated_at)?;
         }
 
-        // 重新加载标签，确保返回的 Task 包含真实有效的标签 ID
-        let tags = Self::get_task_tags(&conn, &task.id)?;
-        Ok(Task { tags, ..task.clone() })
+        // 子任务始终与根任务位于同一清单
+        Self::cascade_list(&tx, &task.id, &task.list_id, task.updated_at)?;
+
+        // 重新加载，确保返回的 Task 包含真实有效的标签 ID 与服务端维护的排序键
+        let updated = Self::find(&tx, &task.id)?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task.id)))?;
+        tx.commit()?;
+
+        Ok(updated)
+    }
+
+    /// 只更新补丁中出现的列，并在服务端刷新 updated_at
+    pub fn patch(db: &Database, task_id: &str, patch: &TaskPatch) -> Result<Task> {
+        let now = chrono::Utc::now().timestamp();
+
+        let mut assignments: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        let mut set = |column: &str, value: Value| {
+            values.push(value);
+            assignments.push(format!("{} = ?{}", column, values.len()));
+        };
+
+        if let Some(title) = &patch.title {
+            set("title", Value::Text(title.clone()));
+        }
+        if let Some(description) = &patch.description {
+            set("description", Value::Text(description.clone()));
+        }
+        if let Some(list_id) = &patch.list_id {
+            set("list_id", Value::Text(list_id.clone()));
+        }
+        if let Some(completed) = patch.completed {
+            set("completed", Value::Integer(completed as i64));
+        }
+        if let Some(priority) = patch.priority {
+            set("priority", Value::Integer(priority.to_i32() as i64));
+        }
+        if let Some(due_date) = patch.due_date {
+            set("due_date", due_date.map_or(Value::Null, Value::Integer));
+        }
+        if let Some(reminder) = &patch.reminder {
+            set("reminder", reminder.clone().map_or(Value::Null, Value::Text));
+        }
+        if let Some(repeat_rule) = &patch.repeat_rule {
+            let json = repeat_rule.as_ref().map(serde_json::to_string).transpose()?;
+            set("repeat_rule", json.map_or(Value::Null, Value::Text));
+        }
+        if let Some(parent_id) = &patch.parent_id {
+            set("parent_id", parent_id.clone().map_or(Value::Null, Value::Text));
+        }
+        if let Some(order) = patch.order {
+            set("order_num", Value::Integer(order as i64));
+        }
+        if let Some(estimated_minutes) = patch.estimated_minutes {
+            set("estimated_minutes", estimated_minutes.map_or(Value::Null, |m| Value::Integer(m as i64)));
+        }
+        if let Some(start_date) = patch.start_date {
+            set("start_date", start_date.map_or(Value::Null, Value::Integer));
+        }
+        if let Some(duration_minutes) = patch.duration_minutes {
+            set("duration_minutes", duration_minutes.map_or(Value::Null, |m| Value::Integer(m as i64)));
+        }
+        if let Some(is_all_day) = patch.is_all_day {
+            set("is_all_day", Value::Integer(is_all_day as i64));
+        }
+        if let Some(section_id) = &patch.section_id {
+            set("section_id", section_id.clone().map_or(Value::Null, Value::Text));
+        }
+        set("updated_at", Value::Integer(now));
+        assignments.push("version = version + 1".to_string());
+
+        // 完成时记录完成时间（已完成的保持原值），取消完成时清空
+        if let Some(completed) = patch.completed {
+            values.push(Value::Integer(now));
+            let now_param = values.len();
+            assignments.push(if completed {
+                format!("completed_at = COALESCE(completed_at, ?{})", now_param)
+            } else {
+                "completed_at = NULL".to_string()
+            });
+        }
+
+        values.push(Value::Text(task_id.to_string()));
+        let mut sql = format!("UPDATE tasks SET {} WHERE id = ?{}", assignments.join(", "), values.len());
+        if let Some(expected_version) = patch.expected_version {
+            values.push(Value::Integer(expected_version));
+            sql.push_str(&format!(" AND version = ?{}", values.len()));
+        }
+
+        {
+            let mut conn = db.conn.lock().unwrap();
+            let tx = conn.transaction()?;
+
+            let rows_affected = tx.execute(&sql, params_from_iter(values))?;
+            if rows_affected == 0 {
+                return Err(Self::stale_write_error(&tx, task_id)?);
+            }
+
+            for tag_id in &patch.remove_tags {
+                Self::detach_tag(&tx, task_id, tag_id)?;
+            }
+            for tag_id in &patch.add_tags {
+                Self::attach_tag(&tx, task_id, tag_id, now)?;
+            }
+
+            if patch.list_id.is_some() {
+                let list_id: String =
+                    tx.query_row("SELECT list_id FROM tasks WHERE id = ?1", params![task_id], |row| row.get(0))?;
+                Self::cascade_list(&tx, task_id, &list_id, now)?;
+            }
+
+            tx.commit()?;
+        }
+
+        Self::get_by_id(db, task_id)
     }
 
     pub fn delete(db: &Database, task_id: &str) -> Result<()> {
@@ -275,7 +377,7 @@ impl TaskRepository {
         
         let now = chrono::Utc::now().timestamp();
         let rows_affected = conn.execute(
-            "UPDATE tasks SET is_deleted = 1, updated_at = ?1 WHERE id = ?2", 
+            "UPDATE tasks SET is_deleted = 1, deleted_at = ?1, updated_at = ?1, version = version + 1 WHERE id = ?2",
             params![now, task_id]
         )?;
         
@@ -291,7 +393,7 @@ impl TaskRepository {
         
         let now = chrono::Utc::now().timestamp();
         let rows_affected = conn.execute(
-            "UPDATE tasks SET is_deleted = 0, updated_at = ?1 WHERE id = ?2", 
+            "UPDATE tasks SET is_deleted = 0, deleted_at = NULL, updated_at = ?1, version = version + 1 WHERE id = ?2",
             params![now, task_id]
         )?;
         
@@ -299,6 +401,13 @@ impl TaskRepository {
             return Err(AppError::NotFound(format!("Task {} not found", task_id)));
         }
 
+        // 所属清单已被删除时，恢复到收集箱
+        conn.execute(
+            "UPDATE tasks SET list_id = ?1, version = version + 1
+             WHERE id = ?2 AND list_id IN (SELECT id FROM lists WHERE is_deleted = 1)",
+            params![INBOX_LIST_ID, task_id],
+        )?;
+
         Ok(())
     }
 
@@ -318,18 +427,179 @@ impl TaskRepository {
         let conn = db.conn.lock().unwrap();
         
         conn.execute("DELETE FROM tasks WHERE is_deleted = 1", [])?;
-        
+        Self::purge_deleted_lists(&conn)?;
+
+        Ok(())
+    }
+
+    /// 永久删除在垃圾桶中停留超过保留期限的任务，返回删除数量；没有删除时间的任务不会被自动清理
+    pub fn purge_trash_before(db: &Database, cutoff: i64) -> Result<usize> {
+        let conn = db.conn.lock().unwrap();
+
+        let purged = conn.execute(
+            "DELETE FROM tasks WHERE is_deleted = 1 AND deleted_at < ?1",
+            params![cutoff],
+        )?;
+        Self::purge_deleted_lists(&conn)?;
+
+        Ok(purged)
+    }
+
+    /// 已软删除且不再被任何任务引用的清单随垃圾桶一起清除
+    fn purge_deleted_lists(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "DELETE FROM lists WHERE is_deleted = 1
+             AND NOT EXISTS (SELECT 1 FROM tasks WHERE tasks.list_id = lists.id)",
+            [],
+        )?;
         Ok(())
     }
 
-    pub fn update_orders(db: &Database, orders: Vec<(String, i32)>) -> Result<()> {
+    /// 把任务连同全部后代移动到目标清单，可同时更换父任务并插入到指定位置。
+    ///
+    /// `position` 为同级任务中的排序位置，缺省时追加到末尾；只写入被移动子树本身。
+    pub fn move_subtree(
+        db: &Database,
+        task_id: &str,
+        list_id: &str,
+        parent_id: Option<&str>,
+        position: Option<i32>,
+    ) -> Result<Task> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if Self::find(&tx, task_id)?.is_none() {
+            return Err(AppError::NotFound(format!("Task {} not found", task_id)));
+        }
+        if let Some(parent_id) = parent_id {
+            if parent_id == task_id || Self::descendant_ids(&tx, task_id)?.iter().any(|id| id == parent_id) {
+                return Err(AppError::InvalidInput(format!(
+                    "Task {} cannot be moved under its own subtree",
+                    task_id
+                )));
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        let sibling_keys = tx
+            .prepare(
+                "SELECT sort_key FROM tasks
+                 WHERE list_id = ?1 AND parent_id IS ?2 AND id != ?3 AND is_deleted = 0
+                 ORDER BY sort_key ASC",
+            )?
+            .query_map(params![list_id, parent_id, task_id], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        let index = position.map_or(sibling_keys.len(), |p| (p.max(0) as usize).min(sibling_keys.len()));
+        let prev = index.checked_sub(1).map(|i| sibling_keys[i].as_str());
+        let next = sibling_keys.get(index).map(String::as_str);
+        let sort_key = Self::key_between_checked(prev, next)?;
+
+        // 换到其他清单时离开原分组
+        tx.execute(
+            "UPDATE tasks SET list_id = ?1, parent_id = ?2, sort_key = ?3, updated_at = ?4, version = version + 1,
+             section_id = CASE WHEN section_id IN (SELECT id FROM sections WHERE list_id = ?1)
+                               THEN section_id ELSE NULL END
+             WHERE id = ?5",
+            params![list_id, parent_id, sort_key, now, task_id],
+        )?;
+        let moved = Self::cascade_list(&tx, task_id, list_id, now)?;
+
+        let task = Self::find(&tx, task_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))?;
+        tx.commit()?;
+
+        tracing::debug!(descendants = moved, "task subtree moved");
+
+        Ok(task)
+    }
+
+    /// `ancestor_id` 是否为 `task_id` 的祖先
+    pub fn is_descendant(db: &Database, ancestor_id: &str, task_id: &str) -> Result<bool> {
+        let conn = db.conn.lock().unwrap();
+        Ok(Self::descendant_ids(&conn, ancestor_id)?.iter().any(|id| id == task_id))
+    }
+
+    fn descendant_ids(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>> {
+        let mut stmt = conn.prepare(DESCENDANTS_SQL)?;
+        let ids = stmt
+            .query_map(params![task_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(ids)
+    }
+
+    /// 把 `root_id` 的所有后代移动到 `list_id`，返回实际移动的任务数。
+    ///
+    /// 分组只属于原清单，移动的后代会离开所在分组。
+    fn cascade_list(conn: &rusqlite::Connection, root_id: &str, list_id: &str, now: i64) -> Result<usize> {
+        let moved = conn.execute(
+            &format!(
+                "UPDATE tasks SET list_id = ?2, section_id = NULL, updated_at = ?3, version = version + 1
+                 WHERE list_id != ?2 AND id IN ({})",
+                DESCENDANTS_SQL
+            ),
+            params![root_id, list_id, now],
+        )?;
+        Ok(moved)
+    }
+
+    /// 把任务放到 `prev_id` 与 `next_id` 之间，只写入被移动的任务
+    pub fn reorder(db: &Database, task_id: &str, prev_id: Option<&str>, next_id: Option<&str>) -> Result<Task> {
+        let conn = db.conn.lock().unwrap();
+
+        let key_of = |id: Option<&str>| -> Result<Option<String>> {
+            id.map(|id| {
+                conn.query_row("SELECT sort_key FROM tasks WHERE id = ?1", params![id], |row| row.get(0))
+                    .optional()?
+                    .ok_or_else(|| AppError::NotFound(format!("Task {} not found", id)))
+            })
+            .transpose()
+        };
+        let prev = key_of(prev_id)?;
+        let next = key_of(next_id)?;
+        let sort_key = Self::key_between_checked(prev.as_deref(), next.as_deref())?;
+
+        let now = chrono::Utc::now().timestamp();
+        let rows_affected = conn.execute(
+            "UPDATE tasks SET sort_key = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+            params![sort_key, now, task_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Task {} not found", task_id)));
+        }
+
+        Self::find(&conn, task_id)?.ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))
+    }
+
+    /// 按给定顺序重排任务：在这些任务原有的排序键之间重新分配，只写入顺序发生变化的任务
+    pub fn update_orders(db: &Database, mut orders: Vec<(String, i32)>) -> Result<()> {
         let mut conn = db.conn.lock().unwrap();
         let tx = conn.transaction()?;
 
         {
-            let mut stmt = tx.prepare("UPDATE tasks SET order_num = ?1 WHERE id = ?2")?;
-            for (id, order_num) in orders {
-                stmt.execute(params![order_num, id])?;
+            orders.sort_by_key(|(_, order_num)| *order_num);
+
+            let mut keys = Vec::with_capacity(orders.len());
+            for (id, _) in &orders {
+                let key: Option<String> = tx
+                    .query_row("SELECT sort_key FROM tasks WHERE id = ?1", params![id], |row| row.get(0))
+                    .optional()?;
+                keys.push(key);
+            }
+            let mut available: Vec<String> = keys.iter().flatten().cloned().collect();
+            available.sort();
+
+            let mut stmt = tx.prepare(
+                "UPDATE tasks SET order_num = ?1, sort_key = ?2, version = version + 1 WHERE id = ?3",
+            )?;
+            let mut available = available.into_iter();
+            for ((id, order_num), current) in orders.iter().zip(&keys) {
+                if current.is_none() {
+                    continue;
+                }
+                let Some(key) = available.next() else { break };
+                if current.as_deref() != Some(key.as_str()) {
+                    stmt.execute(params![order_num, key, id])?;
+                }
             }
         }
 
@@ -337,6 +607,493 @@ impl TaskRepository {
         Ok(())
     }
 
+    /// 重新均匀分配排序键过长或重复的同级任务组，返回更新的任务数。
+    ///
+    /// 只调整排序键，不递增版本号，避免客户端因后台维护产生冲突。
+    pub fn rebalance_sort_keys(db: &Database) -> Result<usize> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let groups = tx
+            .prepare(
+                "SELECT list_id, parent_id FROM tasks GROUP BY list_id, parent_id
+                 HAVING MAX(length(sort_key)) > ?1 OR COUNT(*) > COUNT(DISTINCT sort_key)",
+            )?
+            .query_map(params![ordering::MAX_KEY_LEN], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut updated = 0;
+        for (list_id, parent_id) in &groups {
+            let ids = tx
+                .prepare(
+                    "SELECT id FROM tasks WHERE list_id = ?1 AND parent_id IS ?2
+                     ORDER BY sort_key ASC, created_at DESC",
+                )?
+                .query_map(params![list_id, parent_id], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+
+            let mut stmt = tx.prepare("UPDATE tasks SET sort_key = ?1 WHERE id = ?2")?;
+            for (id, key) in ids.iter().zip(ordering::spread_keys(ids.len())) {
+                updated += stmt.execute(params![key, id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// 相邻排序键必须严格递增，否则说明数据需要重新平衡
+    fn key_between_checked(prev: Option<&str>, next: Option<&str>) -> Result<String> {
+        if let (Some(prev), Some(next)) = (prev, next) {
+            if prev >= next {
+                return Err(AppError::InvalidInput(
+                    "Neighbouring tasks are not in sort order; reload and try again".to_string(),
+                ));
+            }
+        }
+        ordering::key_between(prev, next)
+    }
+
+    /// 在同一个事务中对多个任务执行同一操作。
+    ///
+    /// 每个任务使用独立的保存点，单个任务失败只回滚该任务并记录错误，其余任务照常提交。
+    pub fn bulk_apply(db: &Database, task_ids: &[String], op: &BulkTaskOperation) -> Result<Vec<BulkItemResult>> {
+        let mut conn = db.conn.lock().unwrap();
+        let mut tx = conn.transaction()?;
+
+        let now = chrono::Utc::now().timestamp();
+        let mut results = Vec::with_capacity(task_ids.len());
+
+        for task_id in task_ids {
+            let mut sp = tx.savepoint()?;
+            let outcome = Self::apply_bulk_op(&sp, task_id, op, now).and_then(|()| match op {
+                BulkTaskOperation::DeletePermanently => Ok(None),
+                _ => Self::find(&sp, task_id),
+            });
+
+            let result = match outcome {
+                Ok(task) => {
+                    sp.commit()?;
+                    BulkItemResult { task_id: task_id.clone(), ok: true, task, error: None }
+                }
+                Err(error) => {
+                    sp.rollback()?;
+                    BulkItemResult { task_id: task_id.clone(), ok: false, task: None, error: Some(error) }
+                }
+            };
+            results.push(result);
+        }
+
+        tx.commit()?;
+
+        let failed = results.iter().filter(|r| !r.ok).count();
+        tracing::debug!(total = results.len(), failed, "bulk operation applied");
+
+        Ok(results)
+    }
+
+    fn apply_bulk_op(conn: &rusqlite::Connection, task_id: &str, op: &BulkTaskOperation, now: i64) -> Result<()> {
+        let rows_affected = match op {
+            BulkTaskOperation::Complete => Self::apply_completion(conn, task_id, true, now)?,
+            BulkTaskOperation::Uncomplete => Self::apply_completion(conn, task_id, false, now)?,
+            BulkTaskOperation::MoveToList { list_id } => {
+                // 父任务不在目标清单时脱离父任务成为根任务，后代随之移动
+                let moved = conn.execute(
+                    "UPDATE tasks SET list_id = ?1, updated_at = ?2, version = version + 1,
+                     parent_id = CASE WHEN parent_id IN (SELECT id FROM tasks WHERE list_id = ?1)
+                                      THEN parent_id ELSE NULL END,
+                     section_id = CASE WHEN section_id IN (SELECT id FROM sections WHERE list_id = ?1)
+                                       THEN section_id ELSE NULL END
+                     WHERE id = ?3",
+                    params![list_id, now, task_id],
+                )?;
+                Self::cascade_list(conn, task_id, list_id, now)?;
+                moved
+            }
+            BulkTaskOperation::SetDueDate { due_date } => conn.execute(
+                "UPDATE tasks SET due_date = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+                params![due_date, now, task_id],
+            )?,
+            BulkTaskOperation::SetPriority { priority } => conn.execute(
+                "UPDATE tasks SET priority = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+                params![priority.to_i32(), now, task_id],
+            )?,
+            BulkTaskOperation::AddTag { tag_id } | BulkTaskOperation::RemoveTag { tag_id } => {
+                let touched = conn.execute(
+                    "UPDATE tasks SET updated_at = ?1, version = version + 1 WHERE id = ?2",
+                    params![now, task_id],
+                )?;
+                if touched > 0 {
+                    match op {
+                        BulkTaskOperation::AddTag { .. } => Self::attach_tag(conn, task_id, tag_id, now)?,
+                        _ => Self::detach_tag(conn, task_id, tag_id)?,
+                    }
+                }
+                touched
+            }
+            BulkTaskOperation::Trash => conn.execute(
+                "UPDATE tasks SET is_deleted = 1, deleted_at = ?1, updated_at = ?1, version = version + 1
+                 WHERE id = ?2",
+                params![now, task_id],
+            )?,
+            BulkTaskOperation::Restore => {
+                // 所属清单已被删除时，恢复到收集箱
+                conn.execute(
+                    "UPDATE tasks SET is_deleted = 0, deleted_at = NULL, updated_at = ?1, version = version + 1,
+                     list_id = CASE WHEN list_id IN (SELECT id FROM lists WHERE is_deleted = 1)
+                                    THEN ?2 ELSE list_id END
+                     WHERE id = ?3",
+                    params![now, INBOX_LIST_ID, task_id],
+                )?
+            }
+            BulkTaskOperation::DeletePermanently => {
+                conn.execute("DELETE FROM tasks WHERE id = ?1", params![task_id])?
+            }
+        };
+
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Task {} not found", task_id)));
+        }
+
+        Ok(())
+    }
+
+    /// 设置任务的完成状态，规则见 `apply_completion`
+    pub fn set_completed(db: &Database, task_id: &str, completed: bool) -> Result<Task> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if Self::apply_completion(&tx, task_id, completed, chrono::Utc::now().timestamp())? == 0 {
+            return Err(AppError::NotFound(format!("Task {} not found", task_id)));
+        }
+        let task = Self::find(&tx, task_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))?;
+        tx.commit()?;
+
+        Ok(task)
+    }
+
+    /// 单个切换与批量完成共用的完成逻辑：完成时记录完成时间（已完成的保持原值）并同步完成未完成的子任务，
+    /// 取消完成时清空完成时间。返回更新的任务本身的行数
+    fn apply_completion(conn: &rusqlite::Connection, task_id: &str, completed: bool, now: i64) -> Result<usize> {
+        if !completed {
+            let rows_affected = conn.execute(
+                "UPDATE tasks SET completed = 0, completed_at = NULL, updated_at = ?1, version = version + 1
+                 WHERE id = ?2",
+                params![now, task_id],
+            )?;
+            return Ok(rows_affected);
+        }
+
+        let rows_affected = conn.execute(
+            "UPDATE tasks SET completed = 1, completed_at = COALESCE(completed_at, ?1),
+             updated_at = ?1, version = version + 1 WHERE id = ?2",
+            params![now, task_id],
+        )?;
+        if rows_affected > 0 {
+            conn.execute(
+                "UPDATE tasks SET completed = 1, completed_at = ?1, updated_at = ?1, version = version + 1
+                 WHERE parent_id = ?2 AND completed = 0 AND is_deleted = 0",
+                params![now, task_id],
+            )?;
+        }
+
+        Ok(rows_affected)
+    }
+
+    /// 复制任务及其未删除的子任务树，副本作为兄弟任务紧跟在原任务之后
+    pub fn duplicate(db: &Database, task_id: &str, options: &DuplicateOptions) -> Result<Task> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let original = Self::find(&tx, task_id)?
+            .filter(|task| !task.is_deleted)
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))?;
+
+        let next_key: Option<String> = tx.query_row(
+            "SELECT MIN(sort_key) FROM tasks
+             WHERE list_id = ?1 AND parent_id IS ?2 AND sort_key > ?3 AND is_deleted = 0",
+            params![original.list_id, original.parent_id, original.sort_key],
+            |row| row.get(0),
+        )?;
+        let root_key = ordering::key_between(Some(&original.sort_key), next_key.as_deref())?;
+
+        let tree = Self::load_tree(&tx, "SELECT id, 0 FROM tasks WHERE id = ?1", params![task_id])?;
+        let copies = Self::copy_tree(&tx, tree, &original.list_id, &HashMap::new(), options)?;
+        tx.execute(
+            "UPDATE tasks SET sort_key = ?1 WHERE id = ?2",
+            params![root_key, copies[task_id]],
+        )?;
+
+        let copy = Self::find(&tx, &copies[task_id])?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", copies[task_id])))?;
+        tx.commit()?;
+
+        tracing::debug!(copied = copies.len(), "task duplicated");
+
+        Ok(copy)
+    }
+
+    /// 在一个事务中批量插入任务树，`tasks` 中父任务必须排在子任务之前。
+    ///
+    /// 根任务按给定顺序排在目标同级任务的最前面，子任务沿用传入的排序键。
+    /// 提供 `list` 时先在同一事务中创建该清单。
+    pub fn create_tree(db: &Database, list: Option<&List>, tasks: &[Task]) -> Result<Vec<Task>> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        if let Some(list) = list {
+            ListRepository::insert(&tx, list)?;
+        }
+
+        let is_root = |task: &Task| {
+            task.parent_id.as_ref().is_none_or(|parent_id| tasks.iter().all(|t| &t.id != parent_id))
+        };
+
+        // 倒序插入根任务，每个根任务都排到当前最前面，最终保持给定顺序
+        for root in tasks.iter().rev().filter(|task| is_root(task)) {
+            let first_key: Option<String> = tx.query_row(
+                "SELECT MIN(sort_key) FROM tasks WHERE list_id = ?1 AND parent_id IS ?2 AND sort_key != ''",
+                params![root.list_id, root.parent_id],
+                |row| row.get(0),
+            )?;
+            let sort_key = ordering::key_between(None, first_key.as_deref())?;
+            Self::insert(&tx, &Task { sort_key, ..root.clone() })?;
+        }
+        for task in tasks.iter().filter(|task| !is_root(task)) {
+            Self::insert(&tx, task)?;
+        }
+
+        let mut created = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            created.push(
+                Self::find(&tx, &task.id)?
+                    .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task.id)))?,
+            );
+        }
+        tx.commit()?;
+
+        Ok(created)
+    }
+
+    /// 把清单中的分组与全部未删除的任务树复制到另一个清单，返回复制的任务数
+    pub(crate) fn copy_list_tasks(
+        conn: &rusqlite::Connection,
+        from_list_id: &str,
+        to_list_id: &str,
+        options: &DuplicateOptions,
+    ) -> Result<usize> {
+        let tree = Self::load_tree(
+            conn,
+            "SELECT id, 0 FROM tasks WHERE list_id = ?1 AND parent_id IS NULL AND is_deleted = 0",
+            params![from_list_id],
+        )?;
+        let sections = SectionRepository::copy_to_list(conn, from_list_id, to_list_id)?;
+        Ok(Self::copy_tree(conn, tree, to_list_id, &sections, options)?.len())
+    }
+
+    /// 按层级顺序读取以 `roots_sql` 选出的任务为根的任务树，父任务总在子任务之前
+    fn load_tree<P: rusqlite::Params>(conn: &rusqlite::Connection, roots_sql: &str, params: P) -> Result<Vec<Task>> {
+        Self::query_tasks(
+            conn,
+            &format!(
+                "WITH RECURSIVE tree(node_id, depth) AS (
+                    {}
+                    UNION ALL
+                    SELECT t.id, tree.depth + 1 FROM tasks t JOIN tree ON t.parent_id = tree.node_id
+                    WHERE t.is_deleted = 0
+                )
+                SELECT {} FROM tasks JOIN tree ON tasks.id = tree.node_id
+                ORDER BY tree.depth ASC, tasks.sort_key ASC",
+                roots_sql, TASK_COLUMNS
+            ),
+            params,
+        )
+    }
+
+    /// 以新 ID 插入任务树的副本，返回原 ID 到新 ID 的映射。
+    ///
+    /// 树内的父子关系指向对应的副本；根任务保留原父任务。分组按 `sections` 映射到副本，
+    /// 不在映射中的保持原分组。排序键沿用原值，因此副本在各自的同级任务中保持原有顺序。
+    fn copy_tree(
+        conn: &rusqlite::Connection,
+        tree: Vec<Task>,
+        list_id: &str,
+        sections: &HashMap<String, String>,
+        options: &DuplicateOptions,
+    ) -> Result<HashMap<String, String>> {
+        let now = chrono::Utc::now().timestamp();
+        let mut copies: HashMap<String, String> = HashMap::new();
+
+        for task in tree {
+            let new_id = uuid::Uuid::new_v4().to_string();
+            let mut copy = Task {
+                id: new_id.clone(),
+                list_id: list_id.to_string(),
+                parent_id: task.parent_id.as_ref().map(|p| copies.get(p).unwrap_or(p).clone()),
+                section_id: task.section_id.as_ref().map(|s| sections.get(s).unwrap_or(s).clone()),
+                is_deleted: false,
+                created_at: now,
+                updated_at: now,
+                ..task.clone()
+            };
+            if !options.keep_due_dates {
+                copy.due_date = None;
+                copy.start_date = None;
+                copy.reminder = None;
+            }
+            if !options.keep_completion {
+                copy.completed = false;
+                copy.completed_at = None;
+            }
+
+            Self::insert(conn, &copy)?;
+            copies.insert(task.id, new_id);
+        }
+
+        Ok(copies)
+    }
+
+    /// 插入任务行及其标签关联，排序键使用 `task.sort_key`
+    fn insert(conn: &rusqlite::Connection, task: &Task) -> Result<()> {
+        let repeat_rule_json = task.repeat_rule.as_ref().map(serde_json::to_string).transpose()?;
+
+        conn.execute(
+            "INSERT INTO tasks (id, title, description, list_id, completed, priority, 
+             due_date, reminder, repeat_rule, parent_id, order_num, is_deleted, created_at, updated_at, completed_at,
+             version, sort_key, estimated_minutes, start_date, duration_minutes, is_all_day, section_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, 1, ?16, ?17, ?18, ?19, ?20, ?21)",
+            params![
+                task.id,
+                task.title,
+                task.description,
+                task.list_id,
+                task.completed as i32,
+                task.priority.to_i32(),
+                task.due_date,
+                task.reminder,
+                repeat_rule_json,
+                task.parent_id,
+                task.order,
+                task.is_deleted as i32,
+                task.created_at,
+                task.updated_at,
+                task.completed_at,
+                task.sort_key,
+                task.estimated_minutes,
+                task.start_date,
+                task.duration_minutes,
+                task.is_all_day as i32,
+                task.section_id,
+            ],
+        )?;
+
+        for tag_id in &task.tags {
+            Self::attach_tag(conn, &task.id, tag_id, task.updated_at)?;
+        }
+
+        Ok(())
+    }
+
+    /// 在一个事务中新建 `new_tags` 并给任务打上 `tag_ids` 中的标签，任一步失败时都不会留下新标签
+    pub fn add_tags(db: &Database, task_id: &str, new_tags: &[Tag], tag_ids: &[String]) -> Result<(Task, Vec<Tag>)> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let now = chrono::Utc::now().timestamp();
+        let rows_affected = tx.execute(
+            "UPDATE tasks SET updated_at = ?1, version = version + 1 WHERE id = ?2",
+            params![now, task_id],
+        )?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Task {} not found", task_id)));
+        }
+
+        let created = new_tags.iter().map(|tag| TagRepository::insert(&tx, tag)).collect::<Result<Vec<_>>>()?;
+        for tag_id in tag_ids {
+            Self::attach_tag(&tx, task_id, tag_id, now)?;
+        }
+
+        let task = Self::find(&tx, task_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Task {} not found", task_id)))?;
+        tx.commit()?;
+
+        Ok((task, created))
+    }
+
+    /// 关联标签并记录打标签时间；已存在的关联保持原时间
+    fn attach_tag(conn: &rusqlite::Connection, task_id: &str, tag_id: &str, now: i64) -> Result<()> {
+        conn.execute(
+            "INSERT OR IGNORE INTO task_tags (task_id, tag_id, tagged_at) VALUES (?1, ?2, ?3)",
+            params![task_id, tag_id, now],
+        )?;
+        Ok(())
+    }
+
+    fn detach_tag(conn: &rusqlite::Connection, task_id: &str, tag_id: &str) -> Result<()> {
+        conn.execute(
+            "DELETE FROM task_tags WHERE task_id = ?1 AND tag_id = ?2",
+            params![task_id, tag_id],
+        )?;
+        Ok(())
+    }
+
+    fn find(conn: &rusqlite::Connection, task_id: &str) -> Result<Option<Task>> {
+        let task = conn
+            .query_row(
+                &format!("SELECT {} FROM tasks WHERE id = ?1", TASK_COLUMNS),
+                params![task_id],
+                Self::map_row,
+            )
+            .optional()?;
+
+        match task {
+            Some(task) => {
+                let tags = Self::get_task_tags(conn, task_id)?;
+                Ok(Some(Task { tags, ..task }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// 条件更新未命中时区分「不存在」与「版本过期」
+    fn stale_write_error(conn: &rusqlite::Connection, task_id: &str) -> Result<AppError> {
+        Ok(match Self::find(conn, task_id)? {
+            Some(current) => AppError::conflict(
+                format!("Task {} was modified (current version {})", task_id, current.version),
+                &current,
+            ),
+            None => AppError::NotFound(format!("Task {} not found", task_id)),
+        })
+    }
+
+    fn query_overdue(conn: &rusqlite::Connection, today_start: i64, now: i64) -> Result<Vec<Task>> {
+        Self::query_tasks(
+            conn,
+            &format!(
+                "SELECT {} FROM tasks WHERE is_deleted = 0 AND {} AND {} ORDER BY due_date ASC, created_at DESC",
+                TASK_COLUMNS, OVERDUE_FILTER, VISIBLE_LIST_FILTER
+            ),
+            params![today_start, now],
+        )
+    }
+
+    fn query_tasks<P: rusqlite::Params>(conn: &rusqlite::Connection, sql: &str, params: P) -> Result<Vec<Task>> {
+        let mut stmt = conn.prepare(sql)?;
+        let tasks = stmt.query_map(params, Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Task>>>()?;
+
+        let mut tasks_with_tags = Vec::new();
+        for task in tasks {
+            let tags = Self::get_task_tags(conn, &task.id)?;
+            tasks_with_tags.push(Task { tags, ..task });
+        }
+
+        Ok(tasks_with_tags)
+    }
+
     fn get_task_tags(conn: &rusqlite::Connection, task_id: &str) -> Result<Vec<String>> {
         let mut stmt = conn.prepare(
             "SELECT tt.tag_id FROM task_tags tt
@@ -368,6 +1125,14 @@ impl TaskRepository {
             created_at: row.get(12)?,
             updated_at: row.get(13)?,
             completed_at: row.get(14)?,
+            version: row.get(15)?,
+            sort_key: row.get(16)?,
+            estimated_minutes: row.get(17)?,
+            start_date: row.get(18)?,
+            duration_minutes: row.get(19)?,
+            is_all_day: row.get::<_, i32>(20)? != 0,
+            section_id: row.get(21)?,
+            is_overdue: false,
             tags: Vec::new(),
         })
     }
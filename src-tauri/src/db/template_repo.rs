@@ -0,0 +1,124 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::Template;
+use rusqlite::{params, OptionalExtension};
+
+const TEMPLATE_COLUMNS: &str = "id, name, list, tasks, created_at, updated_at, version";
+
+/// 模板存储：清单信息与任务树以 JSON 保存
+pub struct TemplateRepository;
+
+impl TemplateRepository {
+    pub fn create(db: &Database, template: &Template) -> Result<Template> {
+        let conn = db.conn.lock().unwrap();
+
+        let list_json = template.list.as_ref().map(serde_json::to_string).transpose()?;
+        let tasks_json = serde_json::to_string(&template.tasks)?;
+
+        conn.execute(
+            "INSERT INTO templates (id, name, list, tasks, created_at, updated_at, version)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 1)",
+            params![
+                template.id,
+                template.name,
+                list_json,
+                tasks_json,
+                template.created_at,
+                template.updated_at,
+            ],
+        )?;
+
+        Ok(Template { version: 1, ..template.clone() })
+    }
+
+    pub fn get_by_id(db: &Database, template_id: &str) -> Result<Template> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::find(&conn, template_id)?
+            .ok_or_else(|| AppError::NotFound(format!("Template {} not found", template_id)))
+    }
+
+    pub fn get_all(db: &Database) -> Result<Vec<Template>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM templates ORDER BY name ASC, created_at ASC",
+            TEMPLATE_COLUMNS
+        ))?;
+        let templates = stmt
+            .query_map([], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<Template>>>()?;
+
+        Ok(templates)
+    }
+
+    /// 更新模板。`template.version` 必须与数据库中的版本一致，否则返回冲突
+    pub fn update(db: &Database, template: &Template) -> Result<Template> {
+        let conn = db.conn.lock().unwrap();
+
+        let list_json = template.list.as_ref().map(serde_json::to_string).transpose()?;
+        let tasks_json = serde_json::to_string(&template.tasks)?;
+
+        let rows_affected = conn.execute(
+            "UPDATE templates SET name = ?1, list = ?2, tasks = ?3, updated_at = ?4, version = version + 1
+             WHERE id = ?5 AND version = ?6",
+            params![
+                template.name,
+                list_json,
+                tasks_json,
+                template.updated_at,
+                template.id,
+                template.version,
+            ],
+        )?;
+
+        if rows_affected == 0 {
+            return Err(match Self::find(&conn, &template.id)? {
+                Some(current) => AppError::conflict(
+                    format!("Template {} was modified (current version {})", template.id, current.version),
+                    &current,
+                ),
+                None => AppError::NotFound(format!("Template {} not found", template.id)),
+            });
+        }
+
+        Ok(Template { version: template.version + 1, ..template.clone() })
+    }
+
+    pub fn delete(db: &Database, template_id: &str) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute("DELETE FROM templates WHERE id = ?1", params![template_id])?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Template {} not found", template_id)));
+        }
+
+        Ok(())
+    }
+
+    fn find(conn: &rusqlite::Connection, template_id: &str) -> Result<Option<Template>> {
+        let template = conn
+            .query_row(
+                &format!("SELECT {} FROM templates WHERE id = ?1", TEMPLATE_COLUMNS),
+                params![template_id],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(template)
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<Template> {
+        let list_json: Option<String> = row.get(2)?;
+        let tasks_json: String = row.get(3)?;
+
+        Ok(Template {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            list: list_json.and_then(|json| serde_json::from_str(&json).ok()),
+            tasks: serde_json::from_str(&tasks_json).unwrap_or_default(),
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+            version: row.get(6)?,
+        })
+    }
+}
@@ -0,0 +1,193 @@
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::models::{TimeEntry, TimeReportRow};
+use rusqlite::{params, OptionalExtension};
+
+const TIME_ENTRY_COLUMNS: &str = "id, task_id, started_at, ended_at, note, created_at";
+
+/// 报表统计的任务范围：?1 ~ ?2 内完成或有计时记录的未删除任务，?3 为当前时间
+const REPORT_SCOPE_SQL: &str = "WITH tracked AS (
+        SELECT task_id, SUM(COALESCE(ended_at, ?3) - started_at) AS secs
+        FROM time_entries WHERE started_at BETWEEN ?1 AND ?2
+        GROUP BY task_id
+    ),
+    scope AS (
+        SELECT t.id, t.list_id, COALESCE(t.estimated_minutes, 0) AS estimated, COALESCE(tr.secs, 0) AS secs
+        FROM tasks t LEFT JOIN tracked tr ON tr.task_id = t.id
+        WHERE t.is_deleted = 0 AND (tr.task_id IS NOT NULL OR t.completed_at BETWEEN ?1 AND ?2)
+    )";
+
+/// 任务计时记录
+pub struct TimeEntryRepository;
+
+impl TimeEntryRepository {
+    /// 开始计时；已有计时中的记录时先将其结束
+    pub fn start(db: &Database, task_id: &str, now: i64) -> Result<TimeEntry> {
+        let mut conn = db.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        tx.execute("UPDATE time_entries SET ended_at = ?1 WHERE ended_at IS NULL", params![now])?;
+        let entry = TimeEntry::new(task_id.to_string(), now, None, String::new());
+        Self::insert(&tx, &entry)?;
+        tx.commit()?;
+
+        Ok(entry)
+    }
+
+    /// 结束计时中的记录，没有时返回 `None`
+    pub fn stop(db: &Database, now: i64) -> Result<Option<TimeEntry>> {
+        let conn = db.conn.lock().unwrap();
+
+        let Some(running) = Self::find_running(&conn)? else {
+            return Ok(None);
+        };
+        conn.execute(
+            "UPDATE time_entries SET ended_at = ?1 WHERE id = ?2",
+            params![now.max(running.started_at), running.id],
+        )?;
+
+        Ok(Some(TimeEntry { ended_at: Some(now.max(running.started_at)), ..running }))
+    }
+
+    /// 手动补录一段已结束的计时
+    pub fn create(db: &Database, entry: &TimeEntry) -> Result<TimeEntry> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::insert(&conn, entry)?;
+
+        Ok(entry.clone())
+    }
+
+    pub fn get_running(db: &Database) -> Result<Option<TimeEntry>> {
+        let conn = db.conn.lock().unwrap();
+
+        Self::find_running(&conn)
+    }
+
+    pub fn get_by_task(db: &Database, task_id: &str) -> Result<Vec<TimeEntry>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM time_entries WHERE task_id = ?1 ORDER BY started_at DESC",
+            TIME_ENTRY_COLUMNS
+        ))?;
+        let entries = stmt
+            .query_map(params![task_id], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<TimeEntry>>>()?;
+
+        Ok(entries)
+    }
+
+    pub fn delete(db: &Database, entry_id: &str) -> Result<()> {
+        let conn = db.conn.lock().unwrap();
+
+        let rows_affected = conn.execute("DELETE FROM time_entries WHERE id = ?1", params![entry_id])?;
+        if rows_affected == 0 {
+            return Err(AppError::NotFound(format!("Time entry {} not found", entry_id)));
+        }
+
+        Ok(())
+    }
+
+    /// 任务自身的计时，以及任务与全部未删除子孙任务的预估和计时合计：
+    /// `(tracked_secs, total_estimated_minutes, total_tracked_secs)`
+    pub fn rollup(db: &Database, task_id: &str, now: i64) -> Result<(i64, i64, i64)> {
+        let conn = db.conn.lock().unwrap();
+
+        let totals = conn.query_row(
+            "WITH RECURSIVE subtree(id) AS (
+                SELECT ?1
+                UNION
+                SELECT t.id FROM tasks t JOIN subtree s ON t.parent_id = s.id WHERE t.is_deleted = 0
+            )
+            SELECT
+                (SELECT COALESCE(SUM(COALESCE(ended_at, ?2) - started_at), 0) FROM time_entries WHERE task_id = ?1),
+                (SELECT COALESCE(SUM(estimated_minutes), 0) FROM tasks WHERE id IN (SELECT id FROM subtree)),
+                (SELECT COALESCE(SUM(COALESCE(ended_at, ?2) - started_at), 0)
+                 FROM time_entries WHERE task_id IN (SELECT id FROM subtree))",
+            params![task_id, now],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        Ok(totals)
+    }
+
+    /// 按清单汇总 `[start, end]` 内的预估与实际耗时；清单名称未本地化
+    pub fn report_by_list(db: &Database, start: i64, end: i64, now: i64) -> Result<Vec<TimeReportRow>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "{} SELECT l.id, l.name, COUNT(*), SUM(s.estimated), SUM(s.secs)
+             FROM scope s JOIN lists l ON l.id = s.list_id
+             GROUP BY l.id
+             ORDER BY SUM(s.secs) DESC, l.sort_key ASC",
+            REPORT_SCOPE_SQL
+        ))?;
+        let rows = stmt
+            .query_map(params![start, end, now], Self::map_report_row)?
+            .collect::<rusqlite::Result<Vec<TimeReportRow>>>()?;
+
+        Ok(rows)
+    }
+
+    /// 按标签汇总 `[start, end]` 内的预估与实际耗时，带多个标签的任务计入每个标签
+    pub fn report_by_tag(db: &Database, start: i64, end: i64, now: i64) -> Result<Vec<TimeReportRow>> {
+        let conn = db.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(&format!(
+            "{} SELECT tg.id, tg.name, COUNT(*), SUM(s.estimated), SUM(s.secs)
+             FROM scope s
+             JOIN task_tags tt ON tt.task_id = s.id
+             JOIN tags tg ON tg.id = tt.tag_id
+             GROUP BY tg.id
+             ORDER BY SUM(s.secs) DESC, tg.sort_key ASC",
+            REPORT_SCOPE_SQL
+        ))?;
+        let rows = stmt
+            .query_map(params![start, end, now], Self::map_report_row)?
+            .collect::<rusqlite::Result<Vec<TimeReportRow>>>()?;
+
+        Ok(rows)
+    }
+
+    fn insert(conn: &rusqlite::Connection, entry: &TimeEntry) -> Result<()> {
+        conn.execute(
+            "INSERT INTO time_entries (id, task_id, started_at, ended_at, note, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![entry.id, entry.task_id, entry.started_at, entry.ended_at, entry.note, entry.created_at],
+        )?;
+        Ok(())
+    }
+
+    fn find_running(conn: &rusqlite::Connection) -> Result<Option<TimeEntry>> {
+        let entry = conn
+            .query_row(
+                &format!("SELECT {} FROM time_entries WHERE ended_at IS NULL", TIME_ENTRY_COLUMNS),
+                [],
+                Self::map_row,
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    fn map_row(row: &rusqlite::Row) -> rusqlite::Result<TimeEntry> {
+        Ok(TimeEntry {
+            id: row.get(0)?,
+            task_id: row.get(1)?,
+            started_at: row.get(2)?,
+            ended_at: row.get(3)?,
+            note: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    fn map_report_row(row: &rusqlite::Row) -> rusqlite::Result<TimeReportRow> {
+        Ok(TimeReportRow {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            task_count: row.get(2)?,
+            estimated_minutes: row.get(3)?,
+            tracked_secs: row.get(4)?,
+        })
+    }
+}
@@ -1,9 +1,12 @@
+use rusqlite::ffi;
+use serde::Serialize;
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Database error: {0}")]
-    Database(#[from] rusqlite::Error),
+    Database(rusqlite::Error),
 
     #[error("Not found: {0}")]
     NotFound(String),
@@ -11,6 +14,28 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
+    #[error("Validation failed: {}", format_field_errors(.0))]
+    Validation(Vec<FieldError>),
+
+    #[error("Conflict: {message}")]
+    Conflict {
+        message: String,
+        /// 冲突时数据库中的当前记录，便于前端合并
+        current: Option<Value>,
+    },
+
+    #[error("Foreign key violation: {0}")]
+    ForeignKey(String),
+
+    #[error("Database busy: {0}")]
+    Busy(String),
+
+    #[error("Protected list: {0}")]
+    ProtectedList(String),
+
+    #[error("Invalid list target: {0}")]
+    InvalidListTarget(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -18,12 +43,108 @@ pub enum AppError {
     Serialization(#[from] serde_json::Error),
 }
 
+/// 字段级校验错误，`field` 为字段路径，例如 `title` 或 `tags[2]`
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+fn format_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl AppError {
+    /// 版本冲突，附带数据库中的当前记录
+    pub fn conflict<T: Serialize>(message: impl Into<String>, current: &T) -> Self {
+        AppError::Conflict {
+            message: message.into(),
+            current: serde_json::to_value(current).ok(),
+        }
+    }
+
+    /// 供前端区分错误类型的稳定错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(_) => "database",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::Validation(_) => "validation",
+            AppError::Conflict { .. } => "conflict",
+            AppError::ForeignKey(_) => "foreign_key",
+            AppError::Busy(_) => "busy",
+            AppError::ProtectedList(_) => "protected_list",
+            AppError::InvalidListTarget(_) => "invalid_list_target",
+            AppError::Io(_) => "io",
+            AppError::Serialization(_) => "serialization",
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            AppError::Validation(fields) => Some(serde_json::json!({ "fields": fields })),
+            AppError::Conflict { current, .. } => current.as_ref().map(|row| serde_json::json!({ "current": row })),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for AppError {
+    fn from(err: rusqlite::Error) -> Self {
+        match &err {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound("Record not found".to_string()),
+            rusqlite::Error::SqliteFailure(e, message) => {
+                let message = message.clone().unwrap_or_else(|| e.to_string());
+                match e.code {
+                    rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked => {
+                        AppError::Busy(message)
+                    }
+                    rusqlite::ErrorCode::ConstraintViolation => match e.extended_code {
+                        ffi::SQLITE_CONSTRAINT_FOREIGNKEY => AppError::ForeignKey(message),
+                        ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY => {
+                            AppError::Conflict { message, current: None }
+                        }
+                        _ => AppError::Database(err),
+                    },
+                    _ => AppError::Database(err),
+                }
+            }
+            _ => AppError::Database(err),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorPayload {
+    code: &'static str,
+    message: String,
+    details: Option<Value>,
+}
+
 impl serde::Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        ErrorPayload {
+            code: self.code(),
+            message: self.to_string(),
+            details: self.details(),
+        }
+        .serialize(serializer)
     }
 }
 
@@ -0,0 +1,23 @@
+use super::Message;
+use crate::models::{Priority, SmartListType};
+
+pub fn translate(message: Message) -> &'static str {
+    match message {
+        Message::SmartListName(list_type) => match list_type {
+            SmartListType::All => "All",
+            SmartListType::Today => "Today",
+            SmartListType::Week => "Next 7 Days",
+            SmartListType::Inbox => "Inbox",
+            SmartListType::Completed => "Completed",
+            SmartListType::Trash => "Trash",
+            SmartListType::Overdue => "Overdue",
+        },
+        Message::InboxListName => "Inbox",
+        Message::PriorityName(priority) => match priority {
+            Priority::None => "No Priority",
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+        },
+    }
+}
@@ -0,0 +1,29 @@
+mod en_us;
+mod zh_cn;
+
+use crate::models::{Priority, SmartListType};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+/// 后端需要展示给用户的文案
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Message {
+    SmartListName(SmartListType),
+    InboxListName,
+    PriorityName(Priority),
+}
+
+pub fn translate(locale: Locale, message: Message) -> &'static str {
+    match locale {
+        Locale::ZhCn => zh_cn::translate(message),
+        Locale::EnUs => en_us::translate(message),
+    }
+}
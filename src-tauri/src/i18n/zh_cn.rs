@@ -0,0 +1,23 @@
+use super::Message;
+use crate::models::{Priority, SmartListType};
+
+pub fn translate(message: Message) -> &'static str {
+    match message {
+        Message::SmartListName(list_type) => match list_type {
+            SmartListType::All => "所有任务",
+            SmartListType::Today => "今天",
+            SmartListType::Week => "最近7天",
+            SmartListType::Inbox => "收集箱",
+            SmartListType::Completed => "已完成",
+            SmartListType::Trash => "垃圾桶",
+            SmartListType::Overdue => "已过期",
+        },
+        Message::InboxListName => "收集箱",
+        Message::PriorityName(priority) => match priority {
+            Priority::None => "无优先级",
+            Priority::Low => "低优先级",
+            Priority::Medium => "中优先级",
+            Priority::High => "高优先级",
+        },
+    }
+}
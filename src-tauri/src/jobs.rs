@@ -0,0 +1,101 @@
+use crate::commands::focus::emit_focus_event;
+use crate::db::{ListRepository, SectionRepository, TagRepository, TaskRepository};
+use crate::services::focus_service::{FocusTick, FOCUS_FINISHED_EVENT, FOCUS_TICK_EVENT};
+use crate::services::TaskService;
+use crate::state::AppState;
+use std::time::Duration;
+use tauri::AppHandle;
+
+const TRASH_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SORT_KEY_REBALANCE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const FOCUS_TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 按设置中的保留天数定期清理垃圾桶，设置变更后立即重新执行
+pub fn spawn_trash_cleanup(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut settings_rx = state.settings.subscribe();
+
+        loop {
+            let retention_days = settings_rx.borrow_and_update().trash_retention_days;
+            if retention_days > 0 {
+                let cutoff = chrono::Utc::now().timestamp() - i64::from(retention_days) * 86_400;
+                match TaskRepository::purge_trash_before(&state.db, cutoff) {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!(purged, retention_days, "purged expired tasks from trash"),
+                    Err(e) => tracing::error!("failed to purge trash: {}", e),
+                }
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(TRASH_CLEANUP_INTERVAL) => {}
+                changed = settings_rx.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// 定期把过长或重复的排序键重新均匀分配
+pub fn spawn_sort_key_rebalance(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let result = TaskRepository::rebalance_sort_keys(&state.db).and_then(|tasks| {
+                let lists = ListRepository::rebalance_sort_keys(&state.db)?;
+                let tags = TagRepository::rebalance_sort_keys(&state.db)?;
+                Ok((tasks, lists, tags, SectionRepository::rebalance_sort_keys(&state.db)?))
+            });
+            match result {
+                Ok((0, 0, 0, 0)) => {}
+                Ok((tasks, lists, tags, sections)) => {
+                    tracing::info!(tasks, lists, tags, sections, "rebalanced sort keys")
+                }
+                Err(e) => tracing::error!("failed to rebalance sort keys: {}", e),
+            }
+
+            tokio::time::sleep(SORT_KEY_REBALANCE_INTERVAL).await;
+        }
+    });
+}
+
+/// 每秒推进番茄钟：计时中推送 `focus-tick`，阶段走完时写入记录并推送 `focus-finished`
+pub fn spawn_focus_timer(app: AppHandle, state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FOCUS_TICK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            let pomodoro = state.settings.borrow().pomodoro.clone();
+            match state.focus.tick(&state.db, &pomodoro) {
+                Ok(None) => {}
+                Ok(Some(FocusTick::Running(status))) => emit_focus_event(&app, FOCUS_TICK_EVENT, status),
+                Ok(Some(FocusTick::Finished(finished))) => {
+                    tracing::info!(phase = finished.session.phase.as_str(), "focus phase finished");
+                    emit_focus_event(&app, FOCUS_FINISHED_EVENT, finished);
+                }
+                Err(e) => tracing::error!("failed to advance focus timer: {}", e),
+            }
+        }
+    });
+}
+
+/// 启动时统计上次启动以来新逾期的任务，结果保存在状态中供前端读取
+pub fn spawn_overdue_report(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        match TaskService::overdue_report_since_last_launch(&state.db, &state.settings()) {
+            Ok(report) => {
+                tracing::info!(
+                    newly_overdue = report.newly_overdue.len(),
+                    total_overdue = report.total_overdue,
+                    "checked overdue tasks since last launch"
+                );
+                let _ = state.overdue_report.set(report);
+            }
+            Err(e) => tracing::error!("failed to check overdue tasks: {}", e),
+        }
+    });
+}
@@ -1,10 +1,16 @@
 pub mod commands;
 pub mod db;
 pub mod error;
+pub mod i18n;
+pub mod jobs;
+pub mod logging;
 pub mod models;
+pub mod ordering;
+pub mod services;
 pub mod state;
+pub mod validation;
 
-use crate::{db::Database, state::AppState};
+use crate::{db::Database, services::SettingsService, state::AppState};
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -20,13 +26,27 @@ pub fn run() {
             
             // 确保目录存在
             std::fs::create_dir_all(&app_data_dir)?;
+
+            // 初始化日志，设置加载后再调整级别
+            let log_dir = app.path().app_log_dir().expect("Failed to get app log dir");
+            let log_guard = logging::init(&log_dir).expect("Failed to initialize logging");
+            app.manage(log_guard);
             
             // 初始化数据库
             let db_path = app_data_dir.join("dida.db");
             let db = Database::new(db_path).expect("Failed to initialize database");
+            let settings = SettingsService::load(&db).expect("Failed to load settings");
+            logging::set_level(settings.log_level);
             
             // 设置应用状态
-            let state = AppState::new(db);
+            let state = AppState::new(db, settings);
+
+            // 启动后台任务
+            jobs::spawn_trash_cleanup(state.clone());
+            jobs::spawn_sort_key_rebalance(state.clone());
+            jobs::spawn_focus_timer(app.handle().clone(), state.clone());
+            jobs::spawn_overdue_report(state.clone());
+
             app.manage(state);
             
             Ok(())
@@ -43,22 +63,95 @@ pub fn run() {
             commands::task::get_tasks,
             commands::task::get_task,
             commands::task::update_task,
+            commands::task::patch_task,
+            commands::task::bulk_update_tasks,
+            commands::task::move_task,
+            commands::task::reorder_task,
+            commands::task::duplicate_task,
             commands::task::delete_task,
             commands::task::undo_delete_task,
             commands::task::delete_task_permanently,
             commands::task::empty_trash,
             commands::task::toggle_task,
+            commands::task::postpone_overdue_tasks,
+            commands::task::get_overdue_report,
             // 清单命令
             commands::list::create_list,
             commands::list::get_lists,
             commands::list::get_list,
             commands::list::update_list,
+            commands::list::reorder_list,
+            commands::list::duplicate_list,
             commands::list::delete_list,
+            commands::list::archive_list,
+            commands::list::unarchive_list,
+            commands::list::rename_smart_list,
+            commands::list::set_smart_list_hidden,
+            // 分组命令
+            commands::section::get_sections,
+            commands::section::create_section,
+            commands::section::rename_section,
+            commands::section::reorder_section,
+            commands::section::delete_section,
             // 标签命令
             commands::tag::create_tag,
             commands::tag::update_tag,
             commands::tag::get_tags,
+            commands::tag::get_recent_tags,
+            commands::tag::reorder_tag,
+            commands::tag::tag_task_by_name,
+            commands::tag::get_tag_tree,
+            commands::tag::move_tag,
+            commands::tag::merge_tags,
             commands::tag::delete_tag,
+            // 番茄钟命令
+            commands::focus::get_focus_status,
+            commands::focus::start_focus,
+            commands::focus::pause_focus,
+            commands::focus::resume_focus,
+            commands::focus::abandon_focus,
+            commands::focus::get_focus_sessions,
+            commands::focus::get_task_focus_total,
+            commands::focus::get_daily_focus_totals,
+            // 计时命令
+            commands::time::start_time_entry,
+            commands::time::stop_time_entry,
+            commands::time::get_running_time_entry,
+            commands::time::log_time_entry,
+            commands::time::get_time_entries,
+            commands::time::delete_time_entry,
+            commands::time::get_task_time_summary,
+            commands::time::get_time_report,
+            // 统计命令
+            commands::stats::get_statistics,
+            commands::stats::export_statistics_csv,
+            // 习惯命令
+            commands::habit::get_habits,
+            commands::habit::get_habit,
+            commands::habit::create_habit,
+            commands::habit::update_habit,
+            commands::habit::delete_habit,
+            commands::habit::check_in_habit,
+            commands::habit::get_habit_check_ins,
+            // 日历命令
+            commands::calendar::get_calendar_range,
+            commands::calendar::get_time_block_conflicts,
+            // 模板命令
+            commands::template::get_templates,
+            commands::template::get_template,
+            commands::template::create_template,
+            commands::template::update_template,
+            commands::template::delete_template,
+            commands::template::save_task_as_template,
+            commands::template::save_list_as_template,
+            commands::template::instantiate_template,
+            // 设置命令
+            commands::settings::get_settings,
+            commands::settings::update_settings,
+            commands::settings::get_locale,
+            commands::settings::set_locale,
+            // 日志命令
+            commands::logs::get_recent_logs,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
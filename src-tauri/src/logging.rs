@@ -0,0 +1,106 @@
+use crate::error::{AppError, Result};
+use crate::models::LogLevel;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
+
+const LOG_FILE_PREFIX: &str = "dida";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+struct Logging {
+    dir: PathBuf,
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+/// 持有后台写日志线程；被 drop 时会把缓冲的日志刷入文件
+pub struct LogGuard(#[allow(dead_code)] WorkerGuard);
+
+/// 初始化日志：按天滚动写入日志目录，最多保留 `MAX_LOG_FILES` 个文件。
+/// 每个命令的 span 在结束时输出耗时（time.busy / time.idle）。
+pub fn init(log_dir: &Path) -> Result<LogGuard> {
+    std::fs::create_dir_all(log_dir)?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let (filter, filter_handle) = reload::Layer::new(build_filter(LogLevel::Info));
+    let stdout_layer = cfg!(debug_assertions).then(|| fmt::layer().with_span_events(FmtSpan::CLOSE));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(
+            fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_span_events(FmtSpan::CLOSE),
+        )
+        .with(stdout_layer)
+        .try_init()
+        .map_err(|e| AppError::Io(std::io::Error::other(e)))?;
+
+    let _ = LOGGING.set(Logging {
+        dir: log_dir.to_path_buf(),
+        filter: filter_handle,
+    });
+
+    Ok(LogGuard(guard))
+}
+
+/// 运行时调整日志级别
+pub fn set_level(level: LogLevel) {
+    if let Some(logging) = LOGGING.get() {
+        if let Err(e) = logging.filter.reload(build_filter(level)) {
+            tracing::warn!("failed to update log level: {}", e);
+        }
+    }
+}
+
+/// 读取最近的日志，按时间顺序返回最后 `max_lines` 行
+pub fn recent_lines(max_lines: usize) -> Result<Vec<String>> {
+    let Some(logging) = LOGGING.get() else {
+        return Ok(Vec::new());
+    };
+
+    // 文件名形如 dida.2025-01-01.log，按名称倒序即为从新到旧
+    let mut files = std::fs::read_dir(&logging.dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut lines: Vec<String> = Vec::new();
+    for path in files.iter().rev() {
+        let content = std::fs::read_to_string(path)?;
+        let mut chunk = content.lines().map(str::to_string).collect::<Vec<_>>();
+        chunk.append(&mut lines);
+        lines = chunk;
+        if lines.len() >= max_lines {
+            break;
+        }
+    }
+
+    let skip = lines.len().saturating_sub(max_lines);
+    Ok(lines.split_off(skip))
+}
+
+/// `RUST_LOG` 优先，其次使用设置中的级别；依赖库只记录警告以上
+fn build_filter(level: LogLevel) -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("warn,dida_clone_lib={}", level.as_str())))
+}
@@ -0,0 +1,34 @@
+use super::Task;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// 日历中的一项：任务本身，或重复任务在范围内展开的虚拟实例
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarItem {
+    pub task: Task,
+    /// 所在日期（用户时区），有开始时间时按开始时间，否则按截止日期
+    pub date: NaiveDate,
+    /// 该实例的开始时间，虚拟实例保留原开始时间的时刻
+    pub start_date: Option<i64>,
+    /// 时间块的结束时间，只有设置了时长的非全天任务才有
+    pub end_date: Option<i64>,
+    /// 该实例的截止时间，虚拟实例保留原截止日期的时刻
+    pub due_date: Option<i64>,
+    /// 是否为未写入数据库的重复实例
+    pub is_occurrence: bool,
+    /// 虚拟实例总是未完成
+    pub completed: bool,
+    /// 只有任务本身会逾期，之后的重复实例要等当前实例完成后才轮到
+    pub is_overdue: bool,
+}
+
+/// 两个未完成任务的时间块重叠
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeBlockConflict {
+    pub task_id: String,
+    pub other_task_id: String,
+    /// 重叠开始的日期（用户时区）
+    pub date: NaiveDate,
+    pub overlap_start: i64,
+    pub overlap_end: i64,
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// 番茄钟阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl FocusPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FocusPhase::Work => "work",
+            FocusPhase::ShortBreak => "short_break",
+            FocusPhase::LongBreak => "long_break",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "work" => Some(FocusPhase::Work),
+            "short_break" => Some(FocusPhase::ShortBreak),
+            "long_break" => Some(FocusPhase::LongBreak),
+            _ => None,
+        }
+    }
+}
+
+/// 已结束的专注记录的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusOutcome {
+    /// 计时走完
+    Completed,
+    /// 中途放弃，已专注的时长仍然计入统计
+    Abandoned,
+}
+
+impl FocusOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FocusOutcome::Completed => "completed",
+            FocusOutcome::Abandoned => "abandoned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "completed" => Some(FocusOutcome::Completed),
+            "abandoned" => Some(FocusOutcome::Abandoned),
+            _ => None,
+        }
+    }
+}
+
+/// `focus_sessions` 表中的一条专注记录
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusSession {
+    pub id: String,
+    /// 关联的任务；任务被永久删除后为 `None`
+    pub task_id: Option<String>,
+    pub phase: FocusPhase,
+    pub outcome: FocusOutcome,
+    /// 计划时长（秒）
+    pub planned_secs: i64,
+    /// 实际计时时长（秒），不含暂停时间
+    pub focused_secs: i64,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+/// 正在进行的计时器状态，随 `focus-tick` 等事件推送给前端
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusStatus {
+    pub session_id: String,
+    pub task_id: Option<String>,
+    pub phase: FocusPhase,
+    pub planned_secs: i64,
+    pub elapsed_secs: i64,
+    pub remaining_secs: i64,
+    pub is_paused: bool,
+    pub started_at: i64,
+    /// 本轮已完成的工作阶段数，达到长休息间隔后清零
+    pub completed_work_sessions: u32,
+}
+
+/// 一个阶段结束时推送的事件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusFinished {
+    pub session: FocusSession,
+    /// 建议的下一阶段，不指定阶段开始计时时使用
+    pub next_phase: FocusPhase,
+}
+
+/// 某个任务的专注统计，只统计工作阶段
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFocusTotal {
+    pub task_id: String,
+    pub focused_secs: i64,
+    pub completed_sessions: i64,
+}
+
+/// 某一天（用户时区）的专注统计，只统计工作阶段
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyFocusTotal {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub focused_secs: i64,
+    pub completed_sessions: i64,
+}
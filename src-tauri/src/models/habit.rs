@@ -0,0 +1,69 @@
+use super::{RepeatRule, RepeatType};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 习惯。与任务分开存储，打卡不会出现在任务列表或「已完成」中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Habit {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    /// 格式见 `Validator::color`
+    pub color: String,
+    /// 打卡频率，`start_date` 为首次打卡日
+    #[serde(default = "default_frequency")]
+    pub frequency: RepeatRule,
+    /// 每天需要打卡的次数，达到后当天算完成
+    #[serde(default = "default_goal_count")]
+    pub goal_count: i32,
+    pub start_date: NaiveDate,
+    #[serde(default)]
+    pub is_archived: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// 乐观锁版本号
+    #[serde(default)]
+    pub version: i64,
+}
+
+fn default_frequency() -> RepeatRule {
+    RepeatRule {
+        repeat_type: RepeatType::Daily,
+        interval: None,
+        days_of_week: None,
+        day_of_month: None,
+        month_of_year: None,
+        end_date: None,
+    }
+}
+
+fn default_goal_count() -> i32 {
+    1
+}
+
+/// 某一天的打卡次数
+#[derive(Debug, Clone, Serialize)]
+pub struct HabitCheckIn {
+    pub habit_id: String,
+    pub date: NaiveDate,
+    pub count: i32,
+    pub updated_at: i64,
+}
+
+/// 带今日进度与连续打卡统计的习惯。
+///
+/// 只有按频率需要打卡的日子参与统计，且只统计最近一年；今天还没完成时不中断连续天数，也不计入完成率。
+#[derive(Debug, Clone, Serialize)]
+pub struct HabitWithStats {
+    #[serde(flatten)]
+    pub habit: Habit,
+    pub today_count: i32,
+    /// 今天是否需要打卡
+    pub due_today: bool,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// 统计范围内需要打卡的日子中完成的比例；还没有需要打卡的日子时为 `None`
+    pub completion_rate: Option<f64>,
+    pub total_check_ins: i64,
+}
@@ -1,14 +1,31 @@
 use serde::{Deserialize, Serialize};
 
+/// 收集箱背后的真实清单。智能清单「收集箱」只是它的视图，
+/// 所有写入收集箱的任务都归属于这个清单。
+pub const INBOX_LIST_ID: &str = "inbox";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct List {
     pub id: String,
     pub name: String,
     pub icon: String,
+    /// 格式见 `Validator::color`
     pub color: String,
     pub is_smart: bool,
     pub order: i32,
+    /// 分数索引排序键，由服务端分配
+    #[serde(default)]
+    pub sort_key: String,
+    #[serde(default)]
+    pub is_archived: bool,
+    #[serde(default)]
+    pub is_deleted: bool,
+    #[serde(default)]
+    pub is_hidden: bool,
     pub created_at: i64,
+    /// 乐观锁版本号
+    #[serde(default)]
+    pub version: i64,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -19,9 +36,35 @@ pub enum SmartListType {
     All,
     Completed,
     Trash,
+    Overdue,
+}
+
+impl SmartListType {
+    pub const ALL: [SmartListType; 7] = [
+        SmartListType::All,
+        SmartListType::Today,
+        SmartListType::Week,
+        SmartListType::Inbox,
+        SmartListType::Completed,
+        SmartListType::Trash,
+        SmartListType::Overdue,
+    ];
+
+    pub fn id(self) -> String {
+        format!("smart_{:?}", self).to_lowercase()
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.id() == id)
+    }
 }
 
 impl List {
+    /// 智能清单与收集箱由系统维护，不允许修改、归档或删除
+    pub fn is_builtin_id(id: &str) -> bool {
+        id == INBOX_LIST_ID || SmartListType::from_id(id).is_some()
+    }
+
     pub fn new(name: String, icon: String, color: String) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -30,28 +73,40 @@ impl List {
             color,
             is_smart: false,
             order: 0,
+            sort_key: String::new(),
+            is_archived: false,
+            is_deleted: false,
+            is_hidden: false,
             created_at: chrono::Utc::now().timestamp(),
+            version: 1,
         }
     }
 
+    /// 创建智能清单。名称留空，读取时按当前语言解析（见 `ListService::localize`）
     pub fn new_smart(list_type: SmartListType) -> Self {
-        let (name, icon) = match list_type {
-            SmartListType::All => ("所有任务".to_string(), "📋".to_string()),
-            SmartListType::Today => ("今天".to_string(), "📅".to_string()),
-            SmartListType::Week => ("最近7天".to_string(), "📆".to_string()),
-            SmartListType::Inbox => ("收集箱".to_string(), "📥".to_string()),
-            SmartListType::Completed => ("已完成".to_string(), "✅".to_string()),
-            SmartListType::Trash => ("垃圾桶".to_string(), "🗑️".to_string()),
+        let icon = match list_type {
+            SmartListType::All => "📋",
+            SmartListType::Today => "📅",
+            SmartListType::Week => "📆",
+            SmartListType::Inbox => "📥",
+            SmartListType::Completed => "✅",
+            SmartListType::Trash => "🗑️",
+            SmartListType::Overdue => "⏰",
         };
 
         Self {
-            id: format!("smart_{:?}", list_type).to_lowercase(),
-            name,
-            icon,
+            id: list_type.id(),
+            name: String::new(),
+            icon: icon.to_string(),
             color: "#3B82F6".to_string(),
             is_smart: true,
             order: 0,
+            sort_key: String::new(),
+            is_archived: false,
+            is_deleted: false,
+            is_hidden: false,
             created_at: chrono::Utc::now().timestamp(),
+            version: 1,
         }
     }
 }
@@ -1,7 +1,25 @@
 pub mod task;
 pub mod list;
 pub mod tag;
+pub mod settings;
+pub mod template;
+pub mod focus;
+pub mod time_entry;
+pub mod stats;
+pub mod habit;
+pub mod calendar;
+pub mod section;
 
-pub use task::{Task, Priority, RepeatType, RepeatRule};
-pub use list::{List, SmartListType};
-pub use tag::Tag;
+pub use task::{
+    BulkItemResult, BulkTaskOperation, DuplicateOptions, OverdueReport, Task, TaskPatch, Priority, RepeatType, RepeatRule,
+};
+pub use list::{List, SmartListType, INBOX_LIST_ID};
+pub use tag::{Tag, TagNode, TagWithStats, TaggedTask};
+pub use settings::{LogLevel, PomodoroSettings, Settings, Theme};
+pub use template::{DueOffset, Template, TemplateInstance, TemplateList, TemplateTask};
+pub use focus::{DailyFocusTotal, FocusFinished, FocusOutcome, FocusPhase, FocusSession, FocusStatus, TaskFocusTotal};
+pub use time_entry::{TaskTimeSummary, TimeEntry, TimeReport, TimeReportRow};
+pub use stats::{BreakdownStat, PeriodStat, Statistics, StatisticsRange};
+pub use habit::{Habit, HabitCheckIn, HabitWithStats};
+pub use calendar::{CalendarItem, TimeBlockConflict};
+pub use section::{Section, SectionGroup, TaskListing};
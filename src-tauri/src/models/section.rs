@@ -0,0 +1,48 @@
+use super::Task;
+use serde::{Deserialize, Serialize};
+
+/// 清单内的看板分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Section {
+    pub id: String,
+    pub list_id: String,
+    pub name: String,
+    /// 分数索引排序键，由服务端分配
+    #[serde(default)]
+    pub sort_key: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// 乐观锁版本号
+    #[serde(default)]
+    pub version: i64,
+}
+
+impl Section {
+    pub fn new(list_id: String, name: String) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            list_id,
+            name,
+            sort_key: String::new(),
+            created_at: now,
+            updated_at: now,
+            version: 1,
+        }
+    }
+}
+
+/// 看板中的一列；`section` 为 `None` 的一列是未分组的任务，子任务跟随根任务所在的分组
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionGroup {
+    pub section: Option<Section>,
+    pub tasks: Vec<Task>,
+}
+
+/// `get_tasks` 的返回值：默认为平铺的任务列表，按分组查询时为看板各列
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TaskListing {
+    Flat(Vec<Task>),
+    Grouped(Vec<SectionGroup>),
+}
@@ -0,0 +1,157 @@
+use crate::i18n::Locale;
+use crate::models::{FocusPhase, INBOX_LIST_ID};
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// 可选的提醒预设，与前端 `REMINDER_OFFSETS` 保持一致
+pub const REMINDER_PRESETS: [&str; 5] = ["on_time", "5m_before", "30m_before", "1h_before", "1d_before"];
+
+/// 缺失的字段取默认值，旧版本保存的设置仍可解析
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// IANA 时区名称，例如 `Asia/Shanghai`
+    pub timezone: String,
+    /// 每周的第一天，0 = 周日 … 6 = 周六
+    pub week_start_day: u8,
+    /// 未指定清单时新任务写入的清单
+    pub default_list_id: String,
+    /// 设置了截止日期但未指定提醒时使用的提醒预设
+    pub default_reminder_offset: Option<String>,
+    /// 垃圾桶中的任务保留天数，0 表示永久保留
+    pub trash_retention_days: u32,
+    pub locale: Locale,
+    pub theme: Theme,
+    /// 写入日志文件的最低级别（设置了 `RUST_LOG` 时以环境变量为准）
+    pub log_level: LogLevel,
+    pub pomodoro: PomodoroSettings,
+}
+
+/// 番茄钟各阶段的时长
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PomodoroSettings {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// 每完成多少个工作阶段进入一次长休息
+    pub long_break_interval: u32,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self { work_minutes: 25, short_break_minutes: 5, long_break_minutes: 15, long_break_interval: 4 }
+    }
+}
+
+impl PomodoroSettings {
+    /// 阶段的计划时长（秒）
+    pub fn duration_secs(&self, phase: FocusPhase) -> i64 {
+        let minutes = match phase {
+            FocusPhase::Work => self.work_minutes,
+            FocusPhase::ShortBreak => self.short_break_minutes,
+            FocusPhase::LongBreak => self.long_break_minutes,
+        };
+        i64::from(minutes) * 60
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+    System,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            timezone: "Asia/Shanghai".to_string(),
+            week_start_day: 1,
+            default_list_id: INBOX_LIST_ID.to_string(),
+            default_reminder_offset: None,
+            trash_retention_days: 30,
+            locale: Locale::default(),
+            theme: Theme::System,
+            log_level: LogLevel::Info,
+            pomodoro: PomodoroSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    /// 用户时区下的今天
+    pub fn today(&self) -> NaiveDate {
+        chrono::Utc::now().with_timezone(&self.tz()).date_naive()
+    }
+
+    /// 时间戳在用户时区下的日期
+    pub fn date_of(&self, timestamp: i64) -> Option<NaiveDate> {
+        chrono::DateTime::from_timestamp(timestamp, 0).map(|dt| dt.with_timezone(&self.tz()).date_naive())
+    }
+
+    /// 截止日期的实际期限：恰好落在某天零点的截止日期表示「当天内」，期限为当天结束
+    pub fn due_deadline(&self, due_date: i64) -> i64 {
+        match self.date_of(due_date).map(|date| self.day_bounds(date)) {
+            Some((start, end)) if start == due_date => end,
+            _ => due_date,
+        }
+    }
+
+    /// 把时间戳移到 `date` 这一天，保留用户时区下的时刻
+    pub fn move_to_date(&self, timestamp: i64, date: NaiveDate) -> i64 {
+        let tz = self.tz();
+        let Some(time) = chrono::DateTime::from_timestamp(timestamp, 0).map(|dt| dt.with_timezone(&tz).time()) else {
+            return timestamp;
+        };
+        tz.from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .unwrap_or_else(|| date.and_time(time).and_utc().timestamp())
+    }
+
+    /// 用户时区下某一天的起止时间戳（闭区间）
+    pub fn day_bounds(&self, date: NaiveDate) -> (i64, i64) {
+        let tz = self.tz();
+        let start = tz
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp());
+        let next_day = date.succ_opt().unwrap_or(date);
+        let end = tz
+            .from_local_datetime(&next_day.and_hms_opt(0, 0, 0).unwrap())
+            .earliest()
+            .map(|dt| dt.timestamp() - 1)
+            .unwrap_or(start + 86_399);
+        (start, end)
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// 统计的日期范围（用户时区，闭区间）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StatisticsRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// 日期范围内的效率统计。
+///
+/// 新建数按 `created_at`、完成数按 `completed_at` 落在范围内统计，不含垃圾桶中的任务。
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    pub range: StatisticsRange,
+    pub created_count: i64,
+    pub completed_count: i64,
+    /// 截至范围结束日（或前一天）连续有完成任务的天数
+    pub current_streak: u32,
+    /// 范围内最长的连续完成天数
+    pub longest_streak: u32,
+    /// 截止日期在范围内且已到期的任务中，逾期完成或仍未完成的比例；没有这样的任务时为 `None`
+    pub overdue_rate: Option<f64>,
+    /// 范围内完成的任务从创建到完成的平均秒数
+    pub avg_completion_secs: Option<i64>,
+    pub daily: Vec<PeriodStat>,
+    /// 按设置中的每周第一天分周
+    pub weekly: Vec<PeriodStat>,
+    pub by_list: Vec<BreakdownStat>,
+    pub by_tag: Vec<BreakdownStat>,
+    pub by_priority: Vec<BreakdownStat>,
+}
+
+/// 某一天或某一周的新建与完成数，`date` 为该周期的第一天
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodStat {
+    pub date: NaiveDate,
+    pub created: i64,
+    pub completed: i64,
+}
+
+/// 按清单、标签或优先级分组的新建与完成数
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakdownStat {
+    pub id: String,
+    pub name: String,
+    pub created: i64,
+    pub completed: i64,
+}
@@ -1,13 +1,22 @@
+use super::Task;
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub id: String,
     pub name: String,
+    /// 格式见 `Validator::color`
     pub color: String,
     pub parent_id: Option<String>,
     pub is_pinned: bool,
     pub created_at: i64,
+    /// 乐观锁版本号
+    #[serde(default)]
+    pub version: i64,
+    /// 分数索引排序键，置顶标签之后按此排序
+    #[serde(default)]
+    pub sort_key: String,
 }
 
 impl Tag {
@@ -19,6 +28,47 @@ impl Tag {
             parent_id,
             is_pinned: false,
             created_at: chrono::Utc::now().timestamp(),
+            version: 1,
+            sort_key: String::new(),
         }
     }
+
+    /// 规范化显示名称：去除首尾空白、合并连续空白，并转为 NFC
+    pub fn normalize_name(name: &str) -> String {
+        name.split_whitespace().collect::<Vec<_>>().join(" ").nfc().collect()
+    }
+
+    /// 判断名称是否重复所用的键：规范化后做 NFKC 兼容分解并转为小写，
+    /// 因此 "Work"、"work" 与全角的 "Ｗｏｒｋ" 视为同一标签
+    pub fn name_key(name: &str) -> String {
+        Self::normalize_name(name).nfkc().collect::<String>().to_lowercase()
+    }
+}
+
+/// 带使用统计的标签，计数只包含未删除的任务
+#[derive(Debug, Clone, Serialize)]
+pub struct TagWithStats {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub open_count: i64,
+    pub completed_count: i64,
+    /// 最近一次给任务打上该标签的时间，从未使用过为 `None`
+    pub last_used_at: Option<i64>,
+}
+
+/// 按名称打标签的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TaggedTask {
+    pub task: Task,
+    /// 名称不存在而新建的标签
+    pub created_tags: Vec<Tag>,
+}
+
+/// 标签树节点。`path` 在查询时由祖先名称拼接而成，重命名父标签后自动反映到所有子标签
+#[derive(Debug, Clone, Serialize)]
+pub struct TagNode {
+    #[serde(flatten)]
+    pub tag: Tag,
+    pub path: String,
+    pub children: Vec<TagNode>,
 }
@@ -1,4 +1,6 @@
-use serde::{Deserialize, Serialize};
+use super::Settings;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -14,10 +16,126 @@ pub struct Task {
     pub tags: Vec<String>,
     pub parent_id: Option<String>,
     pub order: i32,
+    /// 分数索引排序键，由服务端分配，见 `ordering` 模块
+    #[serde(default)]
+    pub sort_key: String,
     pub is_deleted: bool,
     pub created_at: i64,
     pub updated_at: i64,
     pub completed_at: Option<i64>,
+    /// 预估耗时（分钟），不含子任务
+    #[serde(default)]
+    pub estimated_minutes: Option<i32>,
+    /// 开始时间；与 `duration_minutes` 一起构成日历上的时间块
+    #[serde(default)]
+    pub start_date: Option<i64>,
+    /// 时间块时长（分钟），需要设置开始时间
+    #[serde(default)]
+    pub duration_minutes: Option<i32>,
+    /// 全天任务只按日期安排，不占用时间块
+    #[serde(default)]
+    pub is_all_day: bool,
+    /// 所在的看板分组，必须属于任务所在的清单
+    #[serde(default)]
+    pub section_id: Option<String>,
+    /// 是否逾期，由服务端按当前时间计算，不存储
+    #[serde(default)]
+    pub is_overdue: bool,
+    /// 乐观锁版本号，每次写入递增；更新时需携带读取到的版本
+    #[serde(default)]
+    pub version: i64,
+}
+
+/// 任务的部分更新：只有出现的字段会被写入。
+///
+/// 可清空的字段使用 `Option<Option<T>>`：字段缺失表示不修改，`null` 表示清空。
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub list_id: Option<String>,
+    pub completed: Option<bool>,
+    pub priority: Option<Priority>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_date: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub reminder: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub repeat_rule: Option<Option<RepeatRule>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub parent_id: Option<Option<String>>,
+    pub order: Option<i32>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimated_minutes: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub start_date: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub duration_minutes: Option<Option<i32>>,
+    pub is_all_day: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub section_id: Option<Option<String>>,
+    /// 期望的当前版本，提供时版本不一致会返回冲突
+    pub expected_version: Option<i64>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+/// 批量任务操作，以 `type` 字段区分，例如 `{"type": "move_to_list", "list_id": "inbox"}`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulkTaskOperation {
+    Complete,
+    Uncomplete,
+    MoveToList { list_id: String },
+    SetDueDate { due_date: Option<i64> },
+    AddTag { tag_id: String },
+    RemoveTag { tag_id: String },
+    SetPriority { priority: Priority },
+    Trash,
+    Restore,
+    DeletePermanently,
+}
+
+/// 启动时的逾期报告
+#[derive(Debug, Clone, Serialize)]
+pub struct OverdueReport {
+    /// 上次启动的时间，首次启动时为 `None`
+    pub last_launch_at: Option<i64>,
+    /// 上次启动以来新逾期的任务
+    pub newly_overdue: Vec<String>,
+    /// 当前逾期的任务总数
+    pub total_overdue: usize,
+}
+
+/// 批量操作中单个任务的执行结果；失败的任务不影响其余任务
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    pub task_id: String,
+    pub ok: bool,
+    /// 操作后的任务，永久删除时为空
+    pub task: Option<Task>,
+    pub error: Option<crate::error::AppError>,
+}
+
+/// 复制任务或清单时的选项，缺省时清除截止日期与完成状态
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DuplicateOptions {
+    /// 保留截止日期、开始时间与提醒
+    pub keep_due_dates: bool,
+    /// 保留完成状态与完成时间
+    pub keep_completion: bool,
+}
+
+/// 把出现的 `null` 反序列化为 `Some(None)`，与字段缺失区分开
+fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +162,65 @@ pub struct RepeatRule {
     pub end_date: Option<i64>,
 }
 
+impl RepeatRule {
+    /// 以 `anchor` 为首次发生的日期，判断 `date`（用户时区）是否是一次重复。
+    ///
+    /// `custom` 规则按填写的字段推断周期：有 `days_of_week` 按周，有 `month_of_year` 按年，
+    /// 有 `day_of_month` 按月，否则按天。按月、按年重复时若当月没有对应日期，落在当月最后一天。
+    pub fn occurs_on(&self, settings: &Settings, anchor: NaiveDate, date: NaiveDate) -> bool {
+        if date < anchor {
+            return false;
+        }
+        if self.end_date.and_then(|end| settings.date_of(end)).is_some_and(|end| date > end) {
+            return false;
+        }
+
+        let interval = i64::from(self.interval.unwrap_or(1).max(1));
+        let repeat_type = match self.repeat_type {
+            RepeatType::Custom if self.days_of_week.as_ref().is_some_and(|days| !days.is_empty()) => RepeatType::Weekly,
+            RepeatType::Custom if self.month_of_year.is_some() => RepeatType::Yearly,
+            RepeatType::Custom if self.day_of_month.is_some() => RepeatType::Monthly,
+            RepeatType::Custom => RepeatType::Daily,
+            ref other => other.clone(),
+        };
+
+        match repeat_type {
+            RepeatType::None => date == anchor,
+            RepeatType::Daily | RepeatType::Custom => (date - anchor).num_days() % interval == 0,
+            RepeatType::Weekday => date.weekday().number_from_monday() <= 5,
+            RepeatType::Weekly => {
+                let weekday = date.weekday().num_days_from_sunday() as i32;
+                let on_day = match &self.days_of_week {
+                    Some(days) if !days.is_empty() => days.contains(&weekday),
+                    _ => date.weekday() == anchor.weekday(),
+                };
+                // 按周日开始的自然周计算间隔，与 `days_of_week` 的编号一致
+                let anchor_week = anchor - chrono::Days::new(u64::from(anchor.weekday().num_days_from_sunday()));
+                on_day && ((date - anchor_week).num_days() / 7) % interval == 0
+            }
+            RepeatType::Monthly => {
+                let months = i64::from(date.year() - anchor.year()) * 12 + i64::from(date.month()) - i64::from(anchor.month());
+                let day = self.day_of_month.map_or(anchor.day(), |day| day as u32);
+                months % interval == 0 && date.day() == day.min(days_in_month(date))
+            }
+            RepeatType::Yearly => {
+                let month = self.month_of_year.map_or(anchor.month(), |month| month as u32);
+                let day = self.day_of_month.map_or(anchor.day(), |day| day as u32);
+                i64::from(date.year() - anchor.year()) % interval == 0
+                    && date.month() == month
+                    && date.day() == day.min(days_in_month(date))
+            }
+        }
+    }
+}
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub enum Priority {
     None = 0,
@@ -83,10 +260,18 @@ impl Task {
             tags: Vec::new(),
             parent_id: None,
             order: 0,
+            sort_key: String::new(),
             is_deleted: false,
             created_at: now,
             updated_at: now,
             completed_at: None,
+            estimated_minutes: None,
+            start_date: None,
+            duration_minutes: None,
+            is_all_day: false,
+            section_id: None,
+            is_overdue: false,
+            version: 1,
         }
     }
 
@@ -99,4 +284,76 @@ impl Task {
             self.completed_at = None;
         }
     }
+
+    /// 按当前时间计算 `is_overdue`：未完成且已过截止期限（见 `Settings::due_deadline`）
+    pub fn mark_overdue(mut self, settings: &Settings) -> Self {
+        let now = chrono::Utc::now().timestamp();
+        self.is_overdue =
+            !self.completed && !self.is_deleted && self.due_date.is_some_and(|due| settings.due_deadline(due) < now);
+        self
+    }
+
+    /// 时间块的起止时间；全天任务或未设置开始时间、时长的任务没有时间块
+    pub fn time_block(&self) -> Option<(i64, i64)> {
+        if self.is_all_day {
+            return None;
+        }
+        let start = self.start_date?;
+        Some((start, start + i64::from(self.duration_minutes?) * 60))
+    }
+
+    /// 在内存中应用部分更新（不含时间戳与完成时间），用于写入前校验
+    pub fn apply_patch(&mut self, patch: &TaskPatch) {
+        if let Some(title) = &patch.title {
+            self.title = title.clone();
+        }
+        if let Some(description) = &patch.description {
+            self.description = description.clone();
+        }
+        if let Some(list_id) = &patch.list_id {
+            self.list_id = list_id.clone();
+        }
+        if let Some(completed) = patch.completed {
+            self.completed = completed;
+        }
+        if let Some(priority) = patch.priority {
+            self.priority = priority;
+        }
+        if let Some(due_date) = patch.due_date {
+            self.due_date = due_date;
+        }
+        if let Some(reminder) = &patch.reminder {
+            self.reminder = reminder.clone();
+        }
+        if let Some(repeat_rule) = &patch.repeat_rule {
+            self.repeat_rule = repeat_rule.clone();
+        }
+        if let Some(parent_id) = &patch.parent_id {
+            self.parent_id = parent_id.clone();
+        }
+        if let Some(order) = patch.order {
+            self.order = order;
+        }
+        if let Some(estimated_minutes) = patch.estimated_minutes {
+            self.estimated_minutes = estimated_minutes;
+        }
+        if let Some(start_date) = patch.start_date {
+            self.start_date = start_date;
+        }
+        if let Some(duration_minutes) = patch.duration_minutes {
+            self.duration_minutes = duration_minutes;
+        }
+        if let Some(is_all_day) = patch.is_all_day {
+            self.is_all_day = is_all_day;
+        }
+        if let Some(section_id) = &patch.section_id {
+            self.section_id = section_id.clone();
+        }
+        self.tags.retain(|tag_id| !patch.remove_tags.contains(tag_id));
+        for tag_id in &patch.add_tags {
+            if !self.tags.contains(tag_id) {
+                self.tags.push(tag_id.clone());
+            }
+        }
+    }
 }
@@ -0,0 +1,125 @@
+use super::{Priority, RepeatRule};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 可复用的任务树模板。
+///
+/// 标题、描述与清单名称中可以使用 `{{date}}` 等占位符，截止日期以相对实例化时间的偏移量保存。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: String,
+    pub name: String,
+    /// 清单模板：实例化时未指定目标清单则按此新建清单
+    #[serde(default)]
+    pub list: Option<TemplateList>,
+    #[serde(default)]
+    pub tasks: Vec<TemplateTask>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// 乐观锁版本号
+    #[serde(default)]
+    pub version: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateList {
+    pub name: String,
+    pub icon: String,
+    /// 格式见 `Validator::color`
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateTask {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_priority")]
+    pub priority: Priority,
+    /// 相对实例化时间的截止日期偏移，例如 `+2d`、`-1w`、`+3h`
+    #[serde(default)]
+    pub due_offset: Option<String>,
+    #[serde(default)]
+    pub reminder: Option<String>,
+    #[serde(default)]
+    pub estimated_minutes: Option<i32>,
+    #[serde(default)]
+    pub repeat_rule: Option<RepeatRule>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub children: Vec<TemplateTask>,
+}
+
+fn default_priority() -> Priority {
+    Priority::None
+}
+
+/// 截止日期偏移的上限（正负方向各约十年）
+pub const MAX_DUE_OFFSET_DAYS: i64 = 3650;
+
+/// 截止日期偏移：按天偏移的结果落在当天零点（用户时区），按分钟偏移的结果精确到时刻
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueOffset {
+    Days(i64),
+    Minutes(i64),
+}
+
+impl DueOffset {
+    /// 解析 `[+-]N(m|h|d|w)`，例如 `+2d`、`-1w`、`30m`；超出 ±`MAX_DUE_OFFSET_DAYS` 天的偏移无效
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let unit = value.chars().last()?;
+        let amount: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+        let offset = match unit {
+            'm' => DueOffset::Minutes(amount),
+            'h' => DueOffset::Minutes(amount.checked_mul(60)?),
+            'd' => DueOffset::Days(amount),
+            'w' => DueOffset::Days(amount.checked_mul(7)?),
+            _ => return None,
+        };
+        let within_bounds = match offset {
+            DueOffset::Days(days) => days.abs() <= MAX_DUE_OFFSET_DAYS,
+            DueOffset::Minutes(minutes) => minutes.abs() <= MAX_DUE_OFFSET_DAYS * 24 * 60,
+        };
+        within_bounds.then_some(offset)
+    }
+}
+
+/// 实例化模板的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInstance {
+    pub list_id: String,
+    /// 新建的任务，父任务总在子任务之前
+    pub tasks: Vec<super::Task>,
+    /// 模板中引用但已被删除的标签 ID，实例化时被忽略
+    pub skipped_tags: Vec<String>,
+}
+
+/// 用变量替换文本中的 `{{name}}` 占位符，未知的占位符原样保留
+pub fn render_placeholders(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                match variables.get(name) {
+                    Some(value) => rendered.push_str(value),
+                    None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// 一段计时记录。计时中的记录 `ended_at` 为 `None`，同一时间最多只有一条
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub task_id: String,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    #[serde(default)]
+    pub note: String,
+    pub created_at: i64,
+}
+
+impl TimeEntry {
+    pub fn new(task_id: String, started_at: i64, ended_at: Option<i64>, note: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id,
+            started_at,
+            ended_at,
+            note,
+            created_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 任务的预估与实际耗时，`total_*` 汇总了任务自身及全部未删除的子孙任务
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTimeSummary {
+    pub task_id: String,
+    pub estimated_minutes: Option<i32>,
+    /// 计时中的记录按当前时间计算
+    pub tracked_secs: i64,
+    pub total_estimated_minutes: i64,
+    pub total_tracked_secs: i64,
+    pub running_entry: Option<TimeEntry>,
+}
+
+/// 预估与实际耗时报表中的一行，按清单或标签汇总
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeReportRow {
+    pub id: String,
+    pub name: String,
+    pub task_count: i64,
+    pub estimated_minutes: i64,
+    pub tracked_secs: i64,
+}
+
+/// 日期范围内的预估与实际耗时报表。
+///
+/// 统计范围内完成或有计时记录的任务；实际耗时只计入开始于范围内的记录。
+#[derive(Debug, Clone, Serialize)]
+pub struct TimeReport {
+    pub by_list: Vec<TimeReportRow>,
+    pub by_tag: Vec<TimeReportRow>,
+}
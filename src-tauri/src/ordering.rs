@@ -0,0 +1,168 @@
+//! 分数索引排序键。
+//!
+//! 排序键是由 base62 字符组成的字符串，按字节序比较（与 SQLite 默认的 BINARY 排序一致）。
+//! 任意两个键之间总能生成一个新键，因此拖拽排序只需写入被移动的那一行；
+//! 键长度持续增长后由后台任务重新均匀分配。
+
+use crate::error::{AppError, Result};
+
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const BASE: usize = DIGITS.len();
+
+/// 超过该长度的排序键会在后台重新分配
+pub const MAX_KEY_LEN: usize = 16;
+
+/// 生成介于 `prev` 与 `next` 之间的排序键，`None` 表示没有下界 / 上界。
+///
+/// 要求 `prev < next`，两者只含 base62 字符且不以 `'0'` 结尾，否则返回 `InvalidInput`。
+pub fn key_between(prev: Option<&str>, next: Option<&str>) -> Result<String> {
+    let prev = prev.unwrap_or("");
+    if !is_valid_key(prev) || next.is_some_and(|next| next.is_empty() || !is_valid_key(next)) {
+        return Err(AppError::InvalidInput("Invalid sort key".to_string()));
+    }
+    if next.is_some_and(|next| prev >= next) {
+        return Err(AppError::InvalidInput("Sort keys out of order".to_string()));
+    }
+    Ok(midpoint(prev.as_bytes(), next.map(str::as_bytes)))
+}
+
+/// 生成 `count` 个均匀分布的递增排序键，用于初始化与重新平衡
+pub fn spread_keys(count: usize) -> Vec<String> {
+    let mut width = 1;
+    let mut span = BASE;
+    // 至少保留与键数量相当的间隙，重新平衡后还能连续插入
+    while span < (count + 1) * 2 {
+        width += 1;
+        span *= BASE;
+    }
+
+    let step = span / (count + 1);
+    (1..=count).map(|i| encode(i * step, width)).collect()
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.ends_with('0') && key.bytes().all(|c| DIGITS.contains(&c))
+}
+
+fn digit(c: u8) -> usize {
+    DIGITS.iter().position(|&d| d == c).unwrap_or(0)
+}
+
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> String {
+    if let Some(b) = b {
+        // 跳过公共前缀，较短的 a 视为以 '0' 补齐
+        let mut n = 0;
+        while n < b.len() && a.get(n).copied().unwrap_or(b'0') == b[n] {
+            n += 1;
+        }
+        if n > 0 {
+            let prefix = String::from_utf8_lossy(&b[..n]).into_owned();
+            return prefix + &midpoint(a.get(n..).unwrap_or(&[]), Some(&b[n..]));
+        }
+    }
+
+    let da = a.first().map_or(0, |&c| digit(c));
+    let db = b.and_then(|b| b.first()).map_or(BASE, |&c| digit(c));
+
+    if db > da + 1 {
+        (DIGITS[(da + db).div_ceil(2)] as char).to_string()
+    } else if let Some(b) = b.filter(|b| b.len() > 1) {
+        (b[0] as char).to_string()
+    } else {
+        let mut key = (DIGITS[da] as char).to_string();
+        key.push_str(&midpoint(a.get(1..).unwrap_or(&[]), None));
+        key
+    }
+}
+
+/// 定长编码后去掉末尾的 '0'，保持相对顺序不变
+fn encode(mut value: usize, width: usize) -> String {
+    let mut digits = vec![b'0'; width];
+    for slot in digits.iter_mut().rev() {
+        *slot = DIGITS[value % BASE];
+        value /= BASE;
+    }
+    while digits.last() == Some(&b'0') {
+        digits.pop();
+    }
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn between(prev: Option<&str>, next: Option<&str>) -> String {
+        let key = key_between(prev, next).unwrap();
+        assert!(prev.is_none_or(|prev| prev < key.as_str()), "{:?} !< {}", prev, key);
+        assert!(next.is_none_or(|next| key.as_str() < next), "{} !< {:?}", key, next);
+        assert!(!key.ends_with('0'), "{} ends with '0'", key);
+        key
+    }
+
+    #[test]
+    fn key_between_without_bounds() {
+        between(None, None);
+        between(Some("V"), None);
+        between(None, Some("V"));
+        between(Some("z"), None);
+        between(None, Some("1"));
+        between(None, Some("01"));
+    }
+
+    #[test]
+    fn key_between_shared_prefix() {
+        between(Some("abc"), Some("abd"));
+        between(Some("ab"), Some("abV"));
+        between(Some("a"), Some("a01"));
+        between(Some("Vz"), Some("W"));
+    }
+
+    #[test]
+    fn key_between_adjacent_digits() {
+        between(Some("1"), Some("2"));
+        between(Some("y"), Some("z"));
+        between(Some("A"), Some("B1"));
+    }
+
+    #[test]
+    fn key_between_repeated_inserts_stay_ordered() {
+        let (mut low, high) = ("1".to_string(), "2".to_string());
+        for _ in 0..100 {
+            low = between(Some(&low), Some(&high));
+        }
+        let mut high = "z".to_string();
+        for _ in 0..100 {
+            high = between(None, Some(&high));
+        }
+    }
+
+    #[test]
+    fn key_between_spread_keys() {
+        let keys = spread_keys(100);
+        for pair in keys.windows(2) {
+            between(Some(&pair[0]), Some(&pair[1]));
+        }
+        between(None, keys.first().map(String::as_str));
+        between(keys.last().map(String::as_str), None);
+    }
+
+    #[test]
+    fn key_between_rejects_invalid_bounds() {
+        assert!(matches!(key_between(Some("b"), Some("a")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(key_between(Some("a"), Some("a")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(key_between(Some("a0"), None), Err(AppError::InvalidInput(_))));
+        assert!(matches!(key_between(None, Some("")), Err(AppError::InvalidInput(_))));
+        assert!(matches!(key_between(Some("a-"), None), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn spread_keys_strictly_increasing() {
+        for count in [0, 1, 2, 61, 62, 63, 1000, 5000] {
+            let keys = spread_keys(count);
+            assert_eq!(keys.len(), count);
+            assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "count {}", count);
+            assert!(keys.iter().all(|key| !key.is_empty() && !key.ends_with('0')), "count {}", count);
+        }
+    }
+}
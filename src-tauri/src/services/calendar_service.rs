@@ -0,0 +1,148 @@
+use crate::db::{Database, TaskRepository};
+use crate::error::Result;
+use crate::models::{CalendarItem, Settings, Task, TimeBlockConflict};
+use crate::validation::{self, MAX_DURATION_MINUTES};
+use chrono::{Days, NaiveDate};
+
+/// 日历视图数据：按开始时间或截止日期定位任务，展开重复任务，并检查时间块冲突
+pub struct CalendarService;
+
+impl CalendarService {
+    /// `[start_date, end_date]`（用户时区）内的日历项，按开始时间（没有时为截止时间）排序。
+    ///
+    /// 重复任务以数据库中的日期为当前实例，之后符合重复规则的日期生成虚拟实例，不写入数据库。
+    pub fn get_range(
+        db: &Database,
+        settings: &Settings,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<CalendarItem>> {
+        validation::validate_date_range(start_date, end_date)?;
+        Self::items_between(db, settings, start_date, end_date)
+    }
+
+    /// 范围内未完成任务（含重复实例）之间的时间块重叠，每对只报告一次。
+    ///
+    /// 在范围之前开始、持续到范围内的时间块同样参与检查。
+    pub fn conflicts(
+        db: &Database,
+        settings: &Settings,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<TimeBlockConflict>> {
+        validation::validate_date_range(start_date, end_date)?;
+
+        // 时间块最长持续 MAX_DURATION_MINUTES，向前多取这么多天即可覆盖所有延续到范围内的时间块
+        let lead_days = (MAX_DURATION_MINUTES as u64).div_ceil(24 * 60);
+        let lead_start = start_date.checked_sub_days(Days::new(lead_days)).unwrap_or(start_date);
+        let (range_start, _) = settings.day_bounds(start_date);
+
+        let mut blocks: Vec<(String, i64, i64)> = Self::items_between(db, settings, lead_start, end_date)?
+            .into_iter()
+            .filter(|item| !item.completed)
+            .filter_map(|item| Some((item.task.id, item.start_date?, item.end_date?)))
+            .filter(|(_, _, block_end)| *block_end > range_start)
+            .collect();
+        blocks.sort_by_key(|(_, block_start, _)| *block_start);
+
+        let mut conflicts = Vec::new();
+        for (index, (task_id, block_start, block_end)) in blocks.iter().enumerate() {
+            for (other_id, other_start, other_end) in &blocks[index + 1..] {
+                if other_start >= block_end {
+                    break;
+                }
+                if other_id == task_id {
+                    continue;
+                }
+                let overlap_start = *block_start.max(other_start);
+                let overlap_end = *block_end.min(other_end);
+                if overlap_end <= range_start {
+                    continue;
+                }
+                conflicts.push(TimeBlockConflict {
+                    task_id: task_id.clone(),
+                    other_task_id: other_id.clone(),
+                    date: settings.date_of(overlap_start.max(range_start)).unwrap_or(start_date),
+                    overlap_start,
+                    overlap_end,
+                });
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    fn items_between(
+        db: &Database,
+        settings: &Settings,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<CalendarItem>> {
+        let (start, _) = settings.day_bounds(start_date);
+        let (_, end) = settings.day_bounds(end_date);
+
+        let mut items = Vec::new();
+        for task in TaskRepository::get_by_schedule_range(db, start, end)? {
+            let task = task.mark_overdue(settings);
+            let Some((scheduled, anchor)) =
+                task.start_date.or(task.due_date).and_then(|ts| Some((ts, settings.date_of(ts)?)))
+            else {
+                continue;
+            };
+
+            if (start..=end).contains(&scheduled) {
+                items.push(CalendarItem {
+                    date: anchor,
+                    start_date: task.start_date,
+                    end_date: task.time_block().map(|(_, block_end)| block_end),
+                    due_date: task.due_date,
+                    is_occurrence: false,
+                    completed: task.completed,
+                    is_overdue: task.is_overdue,
+                    task: task.clone(),
+                });
+            }
+
+            if let Some(rule) = &task.repeat_rule {
+                let first = start_date.max(anchor.succ_opt().unwrap_or(anchor));
+                for date in first.iter_days().take_while(|date| *date <= end_date) {
+                    if rule.occurs_on(settings, anchor, date) {
+                        items.push(Self::occurrence(settings, &task, anchor, date));
+                    }
+                }
+            }
+        }
+
+        items.sort_by(|a, b| {
+            a.start_date
+                .or(a.due_date)
+                .cmp(&b.start_date.or(b.due_date))
+                .then(a.completed.cmp(&b.completed))
+                .then(b.task.priority.to_i32().cmp(&a.task.priority.to_i32()))
+                .then_with(|| a.task.sort_key.cmp(&b.task.sort_key))
+        });
+
+        Ok(items)
+    }
+
+    /// 把任务平移到重复日期 `date`，开始时间与截止日期保持原来的间隔
+    fn occurrence(settings: &Settings, task: &Task, anchor: NaiveDate, date: NaiveDate) -> CalendarItem {
+        let offset = date - anchor;
+        let shift = |ts: i64| settings.date_of(ts).map_or(ts, |day| settings.move_to_date(ts, day + offset));
+        let start_date = task.start_date.map(shift);
+
+        CalendarItem {
+            date,
+            start_date,
+            end_date: task
+                .time_block()
+                .zip(start_date)
+                .map(|((block_start, block_end), start)| start + (block_end - block_start)),
+            due_date: task.due_date.map(shift),
+            is_occurrence: true,
+            completed: false,
+            is_overdue: false,
+            task: Task { is_overdue: false, ..task.clone() },
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::db::{Database, FocusRepository, TaskRepository};
+use crate::error::{AppError, Result};
+use crate::models::{
+    DailyFocusTotal, FocusFinished, FocusOutcome, FocusPhase, FocusSession, FocusStatus, PomodoroSettings, Settings,
+    TaskFocusTotal,
+};
+use crate::validation;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 计时进行中每秒推送一次，内容为 [`FocusStatus`]
+pub const FOCUS_TICK_EVENT: &str = "focus-tick";
+/// 开始、暂停、继续或放弃后推送，内容为当前的 `Option<FocusStatus>`
+pub const FOCUS_CHANGED_EVENT: &str = "focus-changed";
+/// 阶段计时走完后推送，内容为 [`FocusFinished`]
+pub const FOCUS_FINISHED_EVENT: &str = "focus-finished";
+
+/// 番茄钟计时器。
+///
+/// 计时状态只保存在内存中，由后台任务每秒调用 [`FocusTimer::tick`] 推进；
+/// 阶段走完或被放弃时写入 `focus_sessions`。应用退出时进行中的计时直接丢弃。
+pub struct FocusTimer {
+    state: Mutex<TimerState>,
+}
+
+struct TimerState {
+    active: Option<ActiveSession>,
+    /// 本轮已完成的工作阶段数
+    completed_work: u32,
+    next_phase: FocusPhase,
+}
+
+struct ActiveSession {
+    id: String,
+    task_id: Option<String>,
+    phase: FocusPhase,
+    planned_secs: i64,
+    started_at: i64,
+    /// 暂停前累计的计时
+    elapsed: Duration,
+    /// 最近一次开始或继续的时刻，暂停时为 `None`
+    resumed_at: Option<Instant>,
+}
+
+impl ActiveSession {
+    fn elapsed_secs(&self) -> i64 {
+        let elapsed = self.elapsed + self.resumed_at.map(|at| at.elapsed()).unwrap_or_default();
+        (elapsed.as_secs() as i64).min(self.planned_secs)
+    }
+
+    fn to_session(&self, outcome: FocusOutcome) -> FocusSession {
+        FocusSession {
+            id: self.id.clone(),
+            task_id: self.task_id.clone(),
+            phase: self.phase,
+            outcome,
+            planned_secs: self.planned_secs,
+            focused_secs: self.elapsed_secs(),
+            started_at: self.started_at,
+            ended_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+/// 一次 [`FocusTimer::tick`] 的结果
+pub enum FocusTick {
+    Running(FocusStatus),
+    Finished(FocusFinished),
+}
+
+impl Default for FocusTimer {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(TimerState { active: None, completed_work: 0, next_phase: FocusPhase::Work }),
+        }
+    }
+}
+
+impl FocusTimer {
+    pub fn status(&self) -> Option<FocusStatus> {
+        let state = self.state.lock().unwrap();
+        state.active.as_ref().map(|active| Self::snapshot(&state, active))
+    }
+
+    /// 开始计时。未指定阶段时使用上一阶段结束后建议的阶段
+    pub fn start(
+        &self,
+        db: &Database,
+        pomodoro: &PomodoroSettings,
+        task_id: Option<&str>,
+        phase: Option<FocusPhase>,
+    ) -> Result<FocusStatus> {
+        if let Some(task_id) = task_id {
+            TaskRepository::get_by_id(db, task_id)?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if state.active.is_some() {
+            return Err(AppError::InvalidInput("A focus session is already in progress".to_string()));
+        }
+
+        let phase = phase.unwrap_or(state.next_phase);
+        let active = ActiveSession {
+            id: uuid::Uuid::new_v4().to_string(),
+            task_id: task_id.map(str::to_string),
+            phase,
+            planned_secs: pomodoro.duration_secs(phase),
+            started_at: chrono::Utc::now().timestamp(),
+            elapsed: Duration::ZERO,
+            resumed_at: Some(Instant::now()),
+        };
+        let status = Self::snapshot(&state, &active);
+        state.active = Some(active);
+
+        tracing::debug!(phase = phase.as_str(), "focus session started");
+
+        Ok(status)
+    }
+
+    /// 暂停计时，已暂停时保持不变
+    pub fn pause(&self) -> Result<FocusStatus> {
+        let mut state = self.state.lock().unwrap();
+        let active = state.active.as_mut().ok_or_else(Self::not_running)?;
+        if let Some(resumed_at) = active.resumed_at.take() {
+            active.elapsed += resumed_at.elapsed();
+        }
+
+        let active = state.active.as_ref().ok_or_else(Self::not_running)?;
+        Ok(Self::snapshot(&state, active))
+    }
+
+    /// 继续计时，未暂停时保持不变
+    pub fn resume(&self) -> Result<FocusStatus> {
+        let mut state = self.state.lock().unwrap();
+        let active = state.active.as_mut().ok_or_else(Self::not_running)?;
+        if active.resumed_at.is_none() {
+            active.resumed_at = Some(Instant::now());
+        }
+
+        let active = state.active.as_ref().ok_or_else(Self::not_running)?;
+        Ok(Self::snapshot(&state, active))
+    }
+
+    /// 放弃当前阶段，已计时的部分仍写入记录；放弃休息后下一阶段回到工作
+    pub fn abandon(&self, db: &Database) -> Result<FocusSession> {
+        let mut state = self.state.lock().unwrap();
+        let active = state.active.as_ref().ok_or_else(Self::not_running)?;
+
+        let session = FocusRepository::insert(db, &active.to_session(FocusOutcome::Abandoned))?;
+        state.active = None;
+        state.next_phase = FocusPhase::Work;
+
+        Ok(session)
+    }
+
+    /// 推进计时：暂停或空闲时返回 `None`，走完时写入记录并决定下一阶段
+    pub fn tick(&self, db: &Database, pomodoro: &PomodoroSettings) -> Result<Option<FocusTick>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(active) = state.active.as_ref() else {
+            return Ok(None);
+        };
+        if active.resumed_at.is_none() {
+            return Ok(None);
+        }
+        if active.elapsed_secs() < active.planned_secs {
+            return Ok(Some(FocusTick::Running(Self::snapshot(&state, active))));
+        }
+
+        let session = FocusRepository::insert(db, &active.to_session(FocusOutcome::Completed))?;
+        state.active = None;
+        state.next_phase = match session.phase {
+            FocusPhase::Work => {
+                state.completed_work += 1;
+                if state.completed_work >= pomodoro.long_break_interval {
+                    state.completed_work = 0;
+                    FocusPhase::LongBreak
+                } else {
+                    FocusPhase::ShortBreak
+                }
+            }
+            FocusPhase::ShortBreak | FocusPhase::LongBreak => FocusPhase::Work,
+        };
+
+        Ok(Some(FocusTick::Finished(FocusFinished { session, next_phase: state.next_phase })))
+    }
+
+    fn snapshot(state: &TimerState, active: &ActiveSession) -> FocusStatus {
+        let elapsed_secs = active.elapsed_secs();
+        FocusStatus {
+            session_id: active.id.clone(),
+            task_id: active.task_id.clone(),
+            phase: active.phase,
+            planned_secs: active.planned_secs,
+            elapsed_secs,
+            remaining_secs: active.planned_secs - elapsed_secs,
+            is_paused: active.resumed_at.is_none(),
+            started_at: active.started_at,
+            completed_work_sessions: state.completed_work,
+        }
+    }
+
+    fn not_running() -> AppError {
+        AppError::InvalidInput("No focus session is in progress".to_string())
+    }
+}
+
+/// 专注记录的统计查询
+pub struct FocusService;
+
+impl FocusService {
+    pub fn task_total(db: &Database, task_id: &str) -> Result<TaskFocusTotal> {
+        TaskRepository::get_by_id(db, task_id)?;
+        FocusRepository::task_total(db, task_id)
+    }
+
+    /// 按用户时区逐日统计 `[start_date, end_date]` 内的专注时长，没有记录的日期也会返回
+    pub fn daily_totals(
+        db: &Database,
+        settings: &Settings,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<DailyFocusTotal>> {
+        validation::validate_date_range(start_date, end_date)?;
+
+        let mut totals = BTreeMap::new();
+        let mut date = start_date;
+        while date <= end_date {
+            totals.insert(date, (0, 0));
+            date = match date.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        let tz = settings.tz();
+        let sessions = FocusRepository::get_work_sessions_between(
+            db,
+            settings.day_bounds(start_date).0,
+            settings.day_bounds(end_date).1,
+        )?;
+        for session in sessions {
+            let Some(started) = chrono::DateTime::from_timestamp(session.started_at, 0) else {
+                continue;
+            };
+            if let Some((focused_secs, completed)) = totals.get_mut(&started.with_timezone(&tz).date_naive()) {
+                *focused_secs += session.focused_secs;
+                if session.outcome == FocusOutcome::Completed {
+                    *completed += 1;
+                }
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|(date, (focused_secs, completed_sessions))| DailyFocusTotal {
+                date: date.format("%Y-%m-%d").to_string(),
+                focused_secs,
+                completed_sessions,
+            })
+            .collect())
+    }
+}
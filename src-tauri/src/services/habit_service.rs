@@ -0,0 +1,137 @@
+use crate::db::{Database, HabitRepository};
+use crate::error::{AppError, Result};
+use crate::models::{Habit, HabitCheckIn, HabitWithStats, Settings};
+use crate::validation::{self, MAX_HABIT_CHECK_INS};
+use chrono::{Days, NaiveDate};
+use std::collections::HashMap;
+
+/// 连续天数与完成率只统计最近这么多天
+const STATS_WINDOW_DAYS: u64 = 366;
+
+/// 习惯业务规则：打卡与连续天数、完成率统计
+pub struct HabitService;
+
+impl HabitService {
+    pub fn create(db: &Database, settings: &Settings, habit: &Habit) -> Result<Habit> {
+        let now = chrono::Utc::now().timestamp();
+        let habit = Habit {
+            name: habit.name.trim().to_string(),
+            created_at: now,
+            updated_at: now,
+            ..habit.clone()
+        };
+        validation::validate_habit(&habit, settings.today())?;
+        HabitRepository::create(db, &habit)
+    }
+
+    pub fn update(db: &Database, settings: &Settings, habit: &Habit) -> Result<Habit> {
+        let existing = HabitRepository::get_by_id(db, &habit.id)?;
+        let habit = Habit {
+            name: habit.name.trim().to_string(),
+            created_at: existing.created_at,
+            updated_at: chrono::Utc::now().timestamp(),
+            ..habit.clone()
+        };
+        validation::validate_habit(&habit, settings.today())?;
+        HabitRepository::update(db, &habit)
+    }
+
+    pub fn get(db: &Database, settings: &Settings, habit_id: &str) -> Result<HabitWithStats> {
+        let habit = HabitRepository::get_by_id(db, habit_id)?;
+        Self::with_stats(db, settings, habit)
+    }
+
+    pub fn get_all(db: &Database, settings: &Settings, include_archived: bool) -> Result<Vec<HabitWithStats>> {
+        HabitRepository::get_all(db, include_archived)?
+            .into_iter()
+            .map(|habit| Self::with_stats(db, settings, habit))
+            .collect()
+    }
+
+    /// 打卡。未指定日期时为今天；未指定次数时在当天已有次数上加一，指定为 0 则撤销当天的打卡
+    pub fn check_in(
+        db: &Database,
+        settings: &Settings,
+        habit_id: &str,
+        date: Option<NaiveDate>,
+        count: Option<i32>,
+    ) -> Result<HabitWithStats> {
+        let habit = HabitRepository::get_by_id(db, habit_id)?;
+        let today = settings.today();
+        let date = date.unwrap_or(today);
+
+        if habit.is_archived {
+            return Err(AppError::InvalidInput(format!("Habit {} is archived", habit_id)));
+        }
+        if date > today {
+            return Err(AppError::InvalidInput("Cannot check in on a future date".to_string()));
+        }
+        if date < habit.start_date {
+            return Err(AppError::InvalidInput(format!(
+                "Cannot check in before the habit starts on {}",
+                habit.start_date
+            )));
+        }
+
+        let out_of_range =
+            || AppError::InvalidInput(format!("Check-in count must be between 0 and {}", MAX_HABIT_CHECK_INS));
+        let now = chrono::Utc::now().timestamp();
+        let count = match count {
+            Some(count) if !(0..=MAX_HABIT_CHECK_INS).contains(&count) => return Err(out_of_range()),
+            Some(count) => HabitRepository::set_check_in(db, habit_id, date, count, now)?.count,
+            None => HabitRepository::increment_check_in(db, habit_id, date, MAX_HABIT_CHECK_INS, now)?
+                .ok_or_else(out_of_range)?,
+        };
+        tracing::debug!(%date, count, "habit checked in");
+
+        Self::with_stats(db, settings, habit)
+    }
+
+    pub fn get_check_ins(
+        db: &Database,
+        habit_id: &str,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<HabitCheckIn>> {
+        validation::validate_date_range(start_date, end_date)?;
+        HabitRepository::get_by_id(db, habit_id)?;
+        HabitRepository::get_check_ins(db, habit_id, start_date, end_date)
+    }
+
+    fn with_stats(db: &Database, settings: &Settings, habit: Habit) -> Result<HabitWithStats> {
+        let today = settings.today();
+        let window_start = today
+            .checked_sub_days(Days::new(STATS_WINDOW_DAYS - 1))
+            .map_or(habit.start_date, |date| date.max(habit.start_date));
+        let counts: HashMap<NaiveDate, i32> = HabitRepository::get_check_ins(db, &habit.id, window_start, today)?
+            .into_iter()
+            .map(|check_in| (check_in.date, check_in.count))
+            .collect();
+        let done = |date: &NaiveDate| counts.get(date).is_some_and(|count| *count >= habit.goal_count);
+
+        // 今天还没完成时视为进行中，不参与统计
+        let scheduled: Vec<NaiveDate> = window_start
+            .iter_days()
+            .take_while(|date| *date <= today)
+            .filter(|date| habit.frequency.occurs_on(settings, habit.start_date, *date))
+            .filter(|date| *date != today || done(date))
+            .collect();
+
+        let (mut longest_streak, mut run) = (0, 0);
+        for date in &scheduled {
+            run = if done(date) { run + 1 } else { 0 };
+            longest_streak = longest_streak.max(run);
+        }
+        let completed_days = scheduled.iter().filter(|date| done(date)).count();
+
+        Ok(HabitWithStats {
+            today_count: counts.get(&today).copied().unwrap_or(0),
+            due_today: habit.frequency.occurs_on(settings, habit.start_date, today),
+            current_streak: scheduled.iter().rev().take_while(|date| done(date)).count() as u32,
+            longest_streak,
+            completion_rate: (!scheduled.is_empty()).then(|| completed_days as f64 / scheduled.len() as f64),
+            total_check_ins: HabitRepository::total_check_ins(db, &habit.id)?,
+            habit,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use crate::db::{Database, ListRepository};
+use crate::error::{AppError, Result};
+use crate::i18n::{self, Locale, Message};
+use crate::models::{DuplicateOptions, List, SmartListType, INBOX_LIST_ID};
+use crate::validation;
+
+/// 清单业务规则：内置清单保护、任务归属清单解析
+pub struct ListService;
+
+impl ListService {
+    pub fn get_all(db: &Database, locale: Locale, include_archived: bool, include_hidden: bool) -> Result<Vec<List>> {
+        let lists = ListRepository::get_all(db, include_archived, include_hidden)?;
+        Ok(lists.into_iter().map(|list| Self::localize(list, locale)).collect())
+    }
+
+    pub fn get_by_id(db: &Database, locale: Locale, list_id: &str) -> Result<List> {
+        Ok(Self::localize(ListRepository::get_by_id(db, list_id)?, locale))
+    }
+
+    pub fn create(db: &Database, list: &List) -> Result<List> {
+        let list = Self::prepare_new(list)?;
+        ListRepository::create(db, &list)
+    }
+
+    /// 规范化并校验待创建的清单，不写入数据库
+    pub fn prepare_new(list: &List) -> Result<List> {
+        if list.is_smart {
+            return Err(AppError::InvalidInput("Smart lists cannot be created".to_string()));
+        }
+        Self::ensure_mutable(&list.id)?;
+
+        let list = List {
+            name: list.name.trim().to_string(),
+            created_at: chrono::Utc::now().timestamp(),
+            ..list.clone()
+        };
+        validation::validate_list(&list)?;
+        Ok(list)
+    }
+
+    pub fn update(db: &Database, list: &List) -> Result<List> {
+        Self::ensure_mutable(&list.id)?;
+
+        let list = List { name: list.name.trim().to_string(), ..list.clone() };
+        validation::validate_list(&list)?;
+        ListRepository::update(db, &list)
+    }
+
+    pub fn set_archived(db: &Database, list_id: &str, archived: bool) -> Result<()> {
+        Self::ensure_mutable(list_id)?;
+        ListRepository::set_archived(db, list_id, archived)
+    }
+
+    pub fn delete(db: &Database, list_id: &str, move_tasks_to: Option<&str>) -> Result<()> {
+        Self::ensure_mutable(list_id)?;
+        let target = move_tasks_to.map(Self::resolve_task_list).transpose()?;
+        ListRepository::delete(db, list_id, target.as_deref())
+    }
+
+    /// 复制清单及其任务；智能清单没有自己的任务，不能复制
+    pub fn duplicate(db: &Database, locale: Locale, list_id: &str, options: &DuplicateOptions) -> Result<List> {
+        if SmartListType::from_id(list_id).is_some() {
+            return Err(AppError::InvalidInput(format!("Smart list {} cannot be duplicated", list_id)));
+        }
+        // 收集箱的名称按语言解析，副本需要写入实际名称
+        let name = Self::get_by_id(db, locale, list_id)?.name;
+        ListRepository::duplicate(db, list_id, &name, options)
+    }
+
+    /// 拖拽排序，智能清单与普通清单共用同一套排序键
+    pub fn reorder(
+        db: &Database,
+        locale: Locale,
+        list_id: &str,
+        prev_id: Option<&str>,
+        next_id: Option<&str>,
+    ) -> Result<List> {
+        Ok(Self::localize(ListRepository::reorder(db, list_id, prev_id, next_id)?, locale))
+    }
+
+    /// 重命名智能清单；`None` 或空白名称恢复为当前语言的默认名称
+    pub fn rename_smart_list(db: &Database, locale: Locale, list_id: &str, name: Option<&str>) -> Result<List> {
+        Self::ensure_smart(list_id)?;
+        ListRepository::set_name(db, list_id, name.map(str::trim).unwrap_or_default())?;
+        Self::get_by_id(db, locale, list_id)
+    }
+
+    pub fn set_smart_list_hidden(db: &Database, locale: Locale, list_id: &str, hidden: bool) -> Result<List> {
+        Self::ensure_smart(list_id)?;
+        ListRepository::set_hidden(db, list_id, hidden)?;
+        Self::get_by_id(db, locale, list_id)
+    }
+
+    /// 内置清单未被用户重命名时（名称为空），使用当前语言的默认名称
+    fn localize(list: List, locale: Locale) -> List {
+        let name = Self::localized_name(locale, &list.id, &list.name);
+        List { name, ..list }
+    }
+
+    /// 名称留空的智能清单与收集箱使用当前语言的默认名称
+    pub(crate) fn localized_name(locale: Locale, list_id: &str, name: &str) -> String {
+        if !name.is_empty() {
+            return name.to_string();
+        }
+        let message = match SmartListType::from_id(list_id) {
+            Some(list_type) => Message::SmartListName(list_type),
+            None if list_id == INBOX_LIST_ID => Message::InboxListName,
+            None => return String::new(),
+        };
+        i18n::translate(locale, message).to_string()
+    }
+
+    /// 将任务的目标清单解析为真实清单：收集箱映射到默认清单，其余智能清单拒绝写入
+    pub fn resolve_task_list(list_id: &str) -> Result<String> {
+        match SmartListType::from_id(list_id) {
+            Some(SmartListType::Inbox) => Ok(INBOX_LIST_ID.to_string()),
+            Some(_) => Err(AppError::InvalidListTarget(format!(
+                "Tasks cannot be assigned to smart list {}",
+                list_id
+            ))),
+            None => Ok(list_id.to_string()),
+        }
+    }
+
+    fn ensure_smart(list_id: &str) -> Result<()> {
+        if SmartListType::from_id(list_id).is_none() {
+            return Err(AppError::InvalidInput(format!("List {} is not a smart list", list_id)));
+        }
+        Ok(())
+    }
+
+    fn ensure_mutable(list_id: &str) -> Result<()> {
+        if List::is_builtin_id(list_id) {
+            return Err(AppError::ProtectedList(format!(
+                "Built-in list {} cannot be modified",
+                list_id
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+pub mod calendar_service;
+pub mod focus_service;
+pub mod habit_service;
+pub mod list_service;
+pub mod section_service;
+pub mod settings_service;
+pub mod stats_service;
+pub mod tag_service;
+pub mod task_service;
+pub mod template_service;
+pub mod time_service;
+
+pub use calendar_service::CalendarService;
+pub use focus_service::{FocusService, FocusTimer};
+pub use habit_service::HabitService;
+pub use list_service::ListService;
+pub use section_service::SectionService;
+pub use settings_service::SettingsService;
+pub use stats_service::StatsService;
+pub use tag_service::TagService;
+pub use task_service::TaskService;
+pub use template_service::TemplateService;
+pub use time_service::TimeService;
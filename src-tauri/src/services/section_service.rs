@@ -0,0 +1,81 @@
+use crate::db::{Database, ListRepository, SectionRepository, TaskRepository};
+use crate::error::{AppError, Result};
+use crate::models::{Section, SectionGroup, Settings, SmartListType, Task};
+use crate::services::ListService;
+use crate::validation;
+use std::collections::HashMap;
+
+/// 看板分组业务规则：分组只属于普通清单与收集箱，任务按根任务所在的分组归列
+pub struct SectionService;
+
+impl SectionService {
+    pub fn get_by_list(db: &Database, list_id: &str) -> Result<Vec<Section>> {
+        let list_id = Self::resolve_list(db, list_id)?;
+        SectionRepository::get_by_list(db, &list_id)
+    }
+
+    pub fn create(db: &Database, list_id: &str, name: &str) -> Result<Section> {
+        let list_id = Self::resolve_list(db, list_id)?;
+        let section = Section::new(list_id, name.trim().to_string());
+        validation::validate_section(&section)?;
+        SectionRepository::create(db, &section)
+    }
+
+    pub fn rename(db: &Database, section_id: &str, name: &str) -> Result<Section> {
+        let section = Section { name: name.trim().to_string(), ..SectionRepository::get_by_id(db, section_id)? };
+        validation::validate_section(&section)?;
+        SectionRepository::rename(db, section_id, &section.name)
+    }
+
+    pub fn reorder(db: &Database, section_id: &str, prev_id: Option<&str>, next_id: Option<&str>) -> Result<Section> {
+        SectionRepository::reorder(db, section_id, prev_id, next_id)
+    }
+
+    /// 删除分组；`move_tasks_to` 为 `None` 时其中的任务变为未分组
+    pub fn delete(db: &Database, section_id: &str, move_tasks_to: Option<&str>) -> Result<()> {
+        SectionRepository::delete(db, section_id, move_tasks_to)
+    }
+
+    /// 按分组返回清单中的任务：未分组的一列在最前，其余按分组顺序排列，空分组同样返回。
+    ///
+    /// 子任务跟随根任务所在的列，列内保持清单原有的任务顺序。
+    pub fn group_tasks(db: &Database, settings: &Settings, list_id: &str) -> Result<Vec<SectionGroup>> {
+        let list_id = Self::resolve_list(db, list_id)?;
+        let sections = SectionRepository::get_by_list(db, &list_id)?;
+        let tasks = TaskRepository::get_by_list(db, &list_id, settings)?;
+
+        let columns: HashMap<&str, usize> =
+            sections.iter().enumerate().map(|(index, section)| (section.id.as_str(), index + 1)).collect();
+        let by_id: HashMap<&str, &Task> = tasks.iter().map(|task| (task.id.as_str(), task)).collect();
+        let column_of: Vec<usize> = tasks
+            .iter()
+            .map(|task| {
+                let mut root = task;
+                while let Some(parent) = root.parent_id.as_deref().and_then(|id| by_id.get(id)) {
+                    root = parent;
+                }
+                root.section_id.as_deref().and_then(|id| columns.get(id)).copied().unwrap_or(0)
+            })
+            .collect();
+
+        let mut groups: Vec<SectionGroup> = std::iter::once(None)
+            .chain(sections.into_iter().map(Some))
+            .map(|section| SectionGroup { section, tasks: Vec::new() })
+            .collect();
+        for (task, column) in tasks.into_iter().zip(column_of) {
+            groups[column].tasks.push(task);
+        }
+
+        Ok(groups)
+    }
+
+    /// 收集箱即默认清单；其他智能清单只是视图，没有分组
+    fn resolve_list(db: &Database, list_id: &str) -> Result<String> {
+        if SmartListType::from_id(list_id).is_some_and(|list_type| list_type != SmartListType::Inbox) {
+            return Err(AppError::InvalidListTarget(format!("Smart list {} has no sections", list_id)));
+        }
+        let list_id = ListService::resolve_task_list(list_id)?;
+        ListRepository::get_by_id(db, &list_id)?;
+        Ok(list_id)
+    }
+}
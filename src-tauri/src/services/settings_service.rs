@@ -0,0 +1,90 @@
+use crate::db::{Database, ListRepository, SettingsRepository};
+use crate::error::{AppError, Result};
+use crate::models::settings::REMINDER_PRESETS;
+use crate::models::Settings;
+use crate::services::ListService;
+use chrono_tz::Tz;
+use serde_json::Value;
+
+/// 设置变更后向前端广播的事件名
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// 设置以「字段名 → JSON 值」的形式逐项存放在 settings 表中
+pub struct SettingsService;
+
+impl SettingsService {
+    /// 读取设置，缺失或无法解析的项回退为默认值
+    pub fn load(db: &Database) -> Result<Settings> {
+        let mut settings = serde_json::to_value(Settings::default())?;
+
+        for (key, raw) in SettingsRepository::get_all(db)? {
+            let Some(slot) = settings.get_mut(&key) else {
+                continue;
+            };
+            // 兼容早期直接存放的纯字符串值
+            let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+            let previous = std::mem::replace(slot, value);
+            if serde_json::from_value::<Settings>(settings.clone()).is_err() {
+                settings[&key] = previous;
+            }
+        }
+
+        Ok(serde_json::from_value(settings)?)
+    }
+
+    pub fn update(db: &Database, settings: &Settings) -> Result<Settings> {
+        let settings = Self::validate(db, settings)?;
+
+        let entries = match serde_json::to_value(&settings)? {
+            Value::Object(map) => map.into_iter().map(|(key, value)| (key, value.to_string())).collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+        SettingsRepository::set_many(db, &entries)?;
+
+        Ok(settings)
+    }
+
+    /// 校验并规范化设置
+    fn validate(db: &Database, settings: &Settings) -> Result<Settings> {
+        if settings.timezone.parse::<Tz>().is_err() {
+            return Err(AppError::InvalidInput(format!("Unknown timezone: {}", settings.timezone)));
+        }
+        if settings.week_start_day > 6 {
+            return Err(AppError::InvalidInput(
+                "week_start_day must be between 0 (Sunday) and 6 (Saturday)".to_string(),
+            ));
+        }
+        if settings.trash_retention_days > 3650 {
+            return Err(AppError::InvalidInput(
+                "trash_retention_days must not exceed 3650".to_string(),
+            ));
+        }
+        let pomodoro = &settings.pomodoro;
+        if !(1..=180).contains(&pomodoro.work_minutes) {
+            return Err(AppError::InvalidInput(
+                "pomodoro.work_minutes must be between 1 and 180".to_string(),
+            ));
+        }
+        if !(1..=60).contains(&pomodoro.short_break_minutes) || !(1..=60).contains(&pomodoro.long_break_minutes) {
+            return Err(AppError::InvalidInput(
+                "pomodoro break lengths must be between 1 and 60 minutes".to_string(),
+            ));
+        }
+        if !(1..=12).contains(&pomodoro.long_break_interval) {
+            return Err(AppError::InvalidInput(
+                "pomodoro.long_break_interval must be between 1 and 12".to_string(),
+            ));
+        }
+        if let Some(reminder) = &settings.default_reminder_offset {
+            if !REMINDER_PRESETS.contains(&reminder.as_str()) {
+                return Err(AppError::InvalidInput(format!("Unknown reminder preset: {}", reminder)));
+            }
+        }
+
+        // 默认清单必须是可以承载任务的真实清单
+        let default_list_id = ListService::resolve_task_list(&settings.default_list_id)?;
+        ListRepository::get_by_id(db, &default_list_id)?;
+
+        Ok(Settings { default_list_id, ..settings.clone() })
+    }
+}
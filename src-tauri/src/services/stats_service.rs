@@ -0,0 +1,214 @@
+use crate::db::{Database, StatsRepository};
+use crate::error::Result;
+use crate::i18n::{self, Message};
+use crate::models::{BreakdownStat, PeriodStat, Priority, Settings, Statistics, StatisticsRange};
+use crate::services::ListService;
+use crate::validation;
+use chrono::{Datelike, Days, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+const PRIORITIES: [Priority; 4] = [Priority::High, Priority::Medium, Priority::Low, Priority::None];
+
+/// 效率统计与导出
+pub struct StatsService;
+
+impl StatsService {
+    pub fn get(db: &Database, settings: &Settings, range: StatisticsRange) -> Result<Statistics> {
+        validation::validate_date_range(range.start_date, range.end_date)?;
+
+        let (start, _) = settings.day_bounds(range.start_date);
+        let (_, end) = settings.day_bounds(range.end_date);
+        let in_range = |ts: i64| (start..=end).contains(&ts);
+        let now = chrono::Utc::now().timestamp();
+
+        let mut daily: BTreeMap<NaiveDate, (i64, i64)> = Self::dates(range).map(|date| (date, (0, 0))).collect();
+        let mut by_list: HashMap<String, BreakdownStat> = HashMap::new();
+        let mut by_tag: HashMap<String, BreakdownStat> = HashMap::new();
+        let mut by_priority: HashMap<i32, (i64, i64)> = HashMap::new();
+        let (mut created_count, mut completed_count) = (0, 0);
+        let (mut due_count, mut overdue_count) = (0, 0);
+        let mut completion_secs = Vec::new();
+
+        for fact in StatsRepository::task_facts(db, start, end)? {
+            let created = i64::from(in_range(fact.created_at));
+            let completed = i64::from(fact.completed_at.is_some_and(in_range));
+            created_count += created;
+            completed_count += completed;
+
+            if created == 1 {
+                if let Some(day) = settings.date_of(fact.created_at).and_then(|date| daily.get_mut(&date)) {
+                    day.0 += 1;
+                }
+            }
+            if let Some(completed_at) = fact.completed_at.filter(|_| completed == 1) {
+                if let Some(day) = settings.date_of(completed_at).and_then(|date| daily.get_mut(&date)) {
+                    day.1 += 1;
+                }
+                completion_secs.push((completed_at - fact.created_at).max(0));
+            }
+
+            // 只统计已经到期的任务，尚未到期的未完成任务不算逾期
+            if let Some(deadline) = fact.due_date.filter(|due| in_range(*due)).map(|due| settings.due_deadline(due)) {
+                if deadline < now {
+                    due_count += 1;
+                    if fact.completed_at.is_none_or(|completed_at| completed_at > deadline) {
+                        overdue_count += 1;
+                    }
+                }
+            }
+
+            if created + completed == 0 {
+                continue;
+            }
+            let list_name = ListService::localized_name(settings.locale, &fact.list_id, &fact.list_name);
+            Self::add(&mut by_list, &fact.list_id, &list_name, created, completed);
+            for (tag_id, tag_name) in &fact.tags {
+                Self::add(&mut by_tag, tag_id, tag_name, created, completed);
+            }
+            let priority = by_priority.entry(fact.priority.to_i32()).or_default();
+            priority.0 += created;
+            priority.1 += completed;
+        }
+
+        let completion_dates: BTreeSet<NaiveDate> = StatsRepository::completion_times(db, end)?
+            .into_iter()
+            .filter_map(|ts| settings.date_of(ts))
+            .collect();
+
+        Ok(Statistics {
+            range,
+            created_count,
+            completed_count,
+            current_streak: Self::current_streak(&completion_dates, range.end_date),
+            longest_streak: Self::longest_streak(&completion_dates, range),
+            overdue_rate: (due_count > 0).then(|| overdue_count as f64 / due_count as f64),
+            avg_completion_secs: (!completion_secs.is_empty())
+                .then(|| completion_secs.iter().sum::<i64>() / completion_secs.len() as i64),
+            weekly: Self::weekly(&daily, settings.week_start_day),
+            daily: daily
+                .into_iter()
+                .map(|(date, (created, completed))| PeriodStat { date, created, completed })
+                .collect(),
+            by_list: Self::sorted(by_list),
+            by_tag: Self::sorted(by_tag),
+            by_priority: PRIORITIES
+                .into_iter()
+                .map(|priority| {
+                    let (created, completed) = by_priority.get(&priority.to_i32()).copied().unwrap_or_default();
+                    BreakdownStat {
+                        id: priority.to_i32().to_string(),
+                        name: i18n::translate(settings.locale, Message::PriorityName(priority)).to_string(),
+                        created,
+                        completed,
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    /// 以 `section,key,name,metric,value` 的长表格式导出统计，便于在表格软件中透视
+    pub fn to_csv(stats: &Statistics) -> String {
+        let mut csv = String::from("section,key,name,metric,value\n");
+        let mut row = |section: &str, key: &str, name: &str, metric: &str, value: String| {
+            let _ = writeln!(csv, "{},{},{},{},{}", section, csv_field(key), csv_field(name), metric, value);
+        };
+
+        let range = format!("{}..{}", stats.range.start_date, stats.range.end_date);
+        row("summary", &range, "", "created", stats.created_count.to_string());
+        row("summary", &range, "", "completed", stats.completed_count.to_string());
+        row("summary", &range, "", "current_streak", stats.current_streak.to_string());
+        row("summary", &range, "", "longest_streak", stats.longest_streak.to_string());
+        if let Some(rate) = stats.overdue_rate {
+            row("summary", &range, "", "overdue_rate", format!("{:.4}", rate));
+        }
+        if let Some(secs) = stats.avg_completion_secs {
+            row("summary", &range, "", "avg_completion_secs", secs.to_string());
+        }
+
+        for (section, periods) in [("daily", &stats.daily), ("weekly", &stats.weekly)] {
+            for period in periods {
+                let key = period.date.to_string();
+                row(section, &key, "", "created", period.created.to_string());
+                row(section, &key, "", "completed", period.completed.to_string());
+            }
+        }
+        for (section, rows) in [("list", &stats.by_list), ("tag", &stats.by_tag), ("priority", &stats.by_priority)] {
+            for item in rows {
+                row(section, &item.id, &item.name, "created", item.created.to_string());
+                row(section, &item.id, &item.name, "completed", item.completed.to_string());
+            }
+        }
+
+        csv
+    }
+
+    fn dates(range: StatisticsRange) -> impl Iterator<Item = NaiveDate> {
+        range.start_date.iter_days().take_while(move |date| *date <= range.end_date)
+    }
+
+    fn add(groups: &mut HashMap<String, BreakdownStat>, id: &str, name: &str, created: i64, completed: i64) {
+        let stat = groups.entry(id.to_string()).or_insert_with(|| BreakdownStat {
+            id: id.to_string(),
+            name: name.to_string(),
+            created: 0,
+            completed: 0,
+        });
+        stat.created += created;
+        stat.completed += completed;
+    }
+
+    fn sorted(groups: HashMap<String, BreakdownStat>) -> Vec<BreakdownStat> {
+        let mut stats: Vec<BreakdownStat> = groups.into_values().collect();
+        stats.sort_by(|a, b| {
+            b.completed.cmp(&a.completed).then(b.created.cmp(&a.created)).then_with(|| a.name.cmp(&b.name))
+        });
+        stats
+    }
+
+    /// 把逐日统计按周汇总，首尾不完整的周只包含范围内的天
+    fn weekly(daily: &BTreeMap<NaiveDate, (i64, i64)>, week_start_day: u8) -> Vec<PeriodStat> {
+        let mut weeks: BTreeMap<NaiveDate, (i64, i64)> = BTreeMap::new();
+        for (date, (created, completed)) in daily {
+            let offset = (date.weekday().num_days_from_sunday() + 7 - u32::from(week_start_day)) % 7;
+            let week_start = date.checked_sub_days(Days::new(u64::from(offset))).unwrap_or(*date);
+            let week = weeks.entry(week_start).or_default();
+            week.0 += created;
+            week.1 += completed;
+        }
+        weeks
+            .into_iter()
+            .map(|(date, (created, completed))| PeriodStat { date, created, completed })
+            .collect()
+    }
+
+    /// 截至 `end_date` 的连续完成天数；当天还没有完成任务时从前一天开始计算
+    fn current_streak(dates: &BTreeSet<NaiveDate>, end_date: NaiveDate) -> u32 {
+        let mut day = if dates.contains(&end_date) { Some(end_date) } else { end_date.pred_opt() };
+        let mut streak = 0;
+        while let Some(date) = day.filter(|date| dates.contains(date)) {
+            streak += 1;
+            day = date.pred_opt();
+        }
+        streak
+    }
+
+    fn longest_streak(dates: &BTreeSet<NaiveDate>, range: StatisticsRange) -> u32 {
+        let (mut longest, mut current) = (0, 0);
+        for date in Self::dates(range) {
+            current = if dates.contains(&date) { current + 1 } else { 0 };
+            longest = longest.max(current);
+        }
+        longest
+    }
+}
+
+/// 转义 CSV 字段；以公式字符开头的文本加上单引号，避免在表格软件中被当作公式执行
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) { format!("'{}", value) } else { value.to_string() };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
@@ -0,0 +1,133 @@
+use crate::db::{Database, TagRepository, TaskRepository};
+use crate::error::{AppError, Result};
+use crate::models::{Tag, TagNode, TagWithStats, TaggedTask};
+use crate::validation::{self, Validator, MAX_TAG_NAME_LEN};
+
+/// 标签树路径的分隔符
+const PATH_SEPARATOR: &str = "/";
+
+/// 「最近使用的标签」默认与最多返回的数量
+const DEFAULT_RECENT_TAGS: usize = 10;
+const MAX_RECENT_TAGS: usize = 50;
+
+/// 按名称自动新建的标签使用前端主题的默认标签颜色
+const DEFAULT_TAG_COLOR: &str = "var(--dida-tag-default)";
+
+/// 标签业务规则：写入前规范化并校验字段，维护标签层级
+pub struct TagService;
+
+impl TagService {
+    pub fn create(db: &Database, tag: &Tag) -> Result<Tag> {
+        let tag = Tag { name: Tag::normalize_name(&tag.name), ..tag.clone() };
+        validation::validate_tag(db, &tag)?;
+        TagRepository::create(db, &tag)
+    }
+
+    pub fn update(db: &Database, tag: &Tag) -> Result<Tag> {
+        let existing = TagRepository::get_by_id(db, &tag.id)?;
+        let tag = Tag {
+            name: Tag::normalize_name(&tag.name),
+            created_at: existing.created_at,
+            ..tag.clone()
+        };
+        validation::validate_tag(db, &tag)?;
+        TagRepository::update(db, &tag)
+    }
+
+    /// 按名称给任务打标签，名称不存在时自动新建标签。
+    ///
+    /// 名称按规范化后忽略大小写匹配；任一名称无效时整体拒绝，错误以 `names[i]` 报告。
+    /// 新建标签与打标签在同一事务中完成，失败时不会留下新标签。
+    pub fn tag_task_by_name(db: &Database, task_id: &str, names: &[String]) -> Result<TaggedTask> {
+        TaskRepository::get_by_id(db, task_id)?;
+
+        let mut v = Validator::new();
+        for (index, name) in names.iter().enumerate() {
+            v.required_text(&format!("names[{}]", index), name, MAX_TAG_NAME_LEN);
+        }
+        v.finish()?;
+
+        let mut new_tags: Vec<Tag> = Vec::new();
+        let mut tag_ids = Vec::new();
+        for name in names {
+            let name_key = Tag::name_key(name);
+            let tag = match TagRepository::find_by_name(db, name)? {
+                Some(tag) => tag,
+                None => match new_tags.iter().find(|tag| Tag::name_key(&tag.name) == name_key) {
+                    Some(tag) => tag.clone(),
+                    None => {
+                        let tag = Tag::new(Tag::normalize_name(name), DEFAULT_TAG_COLOR.to_string(), None);
+                        validation::validate_tag(db, &tag)?;
+                        new_tags.push(tag.clone());
+                        tag
+                    }
+                },
+            };
+            if !tag_ids.contains(&tag.id) {
+                tag_ids.push(tag.id);
+            }
+        }
+
+        let (task, created_tags) = TaskRepository::add_tags(db, task_id, &new_tags, &tag_ids)?;
+        if !created_tags.is_empty() {
+            tracing::debug!(created = created_tags.len(), "tags created by name");
+        }
+
+        Ok(TaggedTask { task, created_tags })
+    }
+
+    /// 最近使用过的标签，用于快速添加时的标签建议
+    pub fn get_recent(db: &Database, limit: Option<usize>) -> Result<Vec<TagWithStats>> {
+        let limit = limit.unwrap_or(DEFAULT_RECENT_TAGS).min(MAX_RECENT_TAGS);
+        TagRepository::get_recent(db, limit)
+    }
+
+    /// 以树形结构返回全部标签，同级置顶在前、其余按自定义顺序；父标签不存在的标签视为根标签
+    pub fn get_tree(db: &Database) -> Result<Vec<TagNode>> {
+        let tags = TagRepository::get_all(db)?;
+        let is_root = |tag: &Tag| tag.parent_id.as_ref().is_none_or(|p| tags.iter().all(|t| &t.id != p));
+
+        Ok(tags
+            .iter()
+            .filter(|tag| is_root(tag))
+            .map(|tag| Self::build_node(&tags, tag, ""))
+            .collect())
+    }
+
+    /// 移动标签到新的父标签下，`parent_id` 为 `None` 时成为根标签
+    pub fn move_tag(db: &Database, tag_id: &str, parent_id: Option<&str>) -> Result<Tag> {
+        let tag = TagRepository::get_by_id(db, tag_id)?;
+        let moved = Tag { parent_id: parent_id.map(str::to_string), ..tag };
+        validation::validate_tag(db, &moved)?;
+        TagRepository::set_parent(db, tag_id, parent_id)
+    }
+
+    /// 把源标签合并到目标标签，返回合并后的目标标签
+    pub fn merge(db: &Database, source_id: &str, target_id: &str) -> Result<Tag> {
+        if source_id == target_id {
+            return Err(AppError::InvalidInput("Cannot merge a tag into itself".to_string()));
+        }
+        if TagRepository::is_in_subtree(db, source_id, target_id)? {
+            return Err(AppError::InvalidInput(format!(
+                "Cannot merge tag {} into its own descendant {}",
+                source_id, target_id
+            )));
+        }
+        TagRepository::merge(db, source_id, target_id)
+    }
+
+    fn build_node(tags: &[Tag], tag: &Tag, parent_path: &str) -> TagNode {
+        let path = if parent_path.is_empty() {
+            tag.name.clone()
+        } else {
+            format!("{}{}{}", parent_path, PATH_SEPARATOR, tag.name)
+        };
+        let children = tags
+            .iter()
+            .filter(|child| child.parent_id.as_deref() == Some(tag.id.as_str()))
+            .map(|child| Self::build_node(tags, child, &path))
+            .collect();
+
+        TagNode { tag: tag.clone(), path, children }
+    }
+}
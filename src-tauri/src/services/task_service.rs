@@ -0,0 +1,233 @@
+use crate::db::{Database, ListRepository, SettingsRepository, TaskRepository};
+use crate::error::{AppError, Result};
+use crate::models::{BulkItemResult, BulkTaskOperation, DuplicateOptions, OverdueReport, Settings, Task, TaskPatch};
+use crate::services::ListService;
+use crate::validation;
+
+/// 设置表中记录上次启动时间的键，不属于 `Settings`
+const LAST_LAUNCH_KEY: &str = "last_launch_at";
+
+/// 任务业务规则：写入前解析归属清单、规范化并校验字段
+pub struct TaskService;
+
+impl TaskService {
+    pub fn create(db: &Database, settings: &Settings, task: &Task) -> Result<Task> {
+        let mut task = Self::apply_defaults(task, settings);
+        task.list_id = ListService::resolve_task_list(&task.list_id)?;
+        Self::inherit_parent_list(db, &mut task)?;
+
+        // 时间戳由服务端生成，不信任前端传入的值
+        let now = chrono::Utc::now().timestamp();
+        task.created_at = now;
+        task.updated_at = now;
+        task.is_deleted = false;
+        let task = Self::normalize(task, now);
+
+        validation::validate_task(db, &task)?;
+        TaskRepository::create(db, &task)
+    }
+
+    pub fn update(db: &Database, task: &Task) -> Result<Task> {
+        let existing = TaskRepository::get_by_id(db, &task.id)?;
+
+        let mut task = task.clone();
+        task.list_id = ListService::resolve_task_list(&task.list_id)?;
+        Self::inherit_parent_list(db, &mut task)?;
+        // 换到其他清单且没有重新指定分组时，离开原清单的分组
+        if task.list_id != existing.list_id && task.section_id == existing.section_id {
+            task.section_id = None;
+        }
+
+        let now = chrono::Utc::now().timestamp();
+        task.created_at = existing.created_at;
+        task.updated_at = now;
+        let task = Self::normalize(task, now);
+
+        validation::validate_task(db, &task)?;
+        TaskRepository::update(db, &task)
+    }
+
+    /// 部分更新任务，只写入补丁中出现的字段
+    pub fn patch(db: &Database, task_id: &str, patch: &TaskPatch) -> Result<Task> {
+        let mut patch = patch.clone();
+        if let Some(list_id) = &patch.list_id {
+            patch.list_id = Some(ListService::resolve_task_list(list_id)?);
+        }
+        if let Some(title) = &patch.title {
+            patch.title = Some(title.trim().to_string());
+        }
+
+        let existing = TaskRepository::get_by_id(db, task_id)?;
+        let mut patched = existing.clone();
+        patched.apply_patch(&patch);
+        let list_id = patched.list_id.clone();
+        Self::inherit_parent_list(db, &mut patched)?;
+        if patched.list_id != list_id {
+            patch.list_id = Some(patched.list_id.clone());
+        }
+        // 换到其他清单且补丁没有指定分组时，离开原清单的分组
+        if patched.list_id != existing.list_id && patch.section_id.is_none() && existing.section_id.is_some() {
+            patch.section_id = Some(None);
+            patched.section_id = None;
+        }
+        validation::validate_task_patch(db, &patched, &patch)?;
+
+        TaskRepository::patch(db, task_id, &patch)
+    }
+
+    /// 把全部逾期任务推迟到今天。保留原来的时刻，该时刻今天已过时改为今天内（零点）。
+    ///
+    /// 逐个任务写入并返回各自的结果，单个任务冲突或校验失败不影响其余任务。
+    pub fn postpone_overdue(db: &Database, settings: &Settings) -> Result<Vec<BulkItemResult>> {
+        let now = chrono::Utc::now().timestamp();
+        let today = settings.today();
+        let (today_start, _) = settings.day_bounds(today);
+
+        let mut results = Vec::new();
+        for task in TaskRepository::get_overdue(db, settings, now)? {
+            let Some(due_date) = task.due_date else {
+                continue;
+            };
+            let moved = settings.move_to_date(due_date, today);
+            let patch = TaskPatch {
+                due_date: Some(Some(if moved > now { moved } else { today_start })),
+                expected_version: Some(task.version),
+                ..Default::default()
+            };
+            results.push(match Self::patch(db, &task.id, &patch) {
+                Ok(task) => BulkItemResult { task_id: task.id.clone(), ok: true, task: Some(task), error: None },
+                Err(error) => BulkItemResult { task_id: task.id, ok: false, task: None, error: Some(error) },
+            });
+        }
+
+        let failed = results.iter().filter(|r| !r.ok).count();
+        tracing::info!(total = results.len(), failed, "postponed overdue tasks to today");
+        Ok(results)
+    }
+
+    /// 统计上次启动以来新逾期的任务，并把本次启动时间记为上次启动时间
+    pub fn overdue_report_since_last_launch(db: &Database, settings: &Settings) -> Result<OverdueReport> {
+        let now = chrono::Utc::now().timestamp();
+        let last_launch_at = SettingsRepository::get(db, LAST_LAUNCH_KEY)?.and_then(|value| value.parse().ok());
+
+        let overdue = TaskRepository::get_overdue(db, settings, now)?;
+        let newly_overdue = overdue
+            .iter()
+            .filter(|task| {
+                let deadline = task.due_date.map(|due| settings.due_deadline(due));
+                last_launch_at.zip(deadline).is_some_and(|(last_launch_at, deadline)| deadline >= last_launch_at)
+            })
+            .map(|task| task.id.clone())
+            .collect();
+
+        SettingsRepository::set(db, LAST_LAUNCH_KEY, &now.to_string())?;
+
+        Ok(OverdueReport { last_launch_at, newly_overdue, total_overdue: overdue.len() })
+    }
+
+    /// 把任务及其全部子任务移动到目标位置。
+    ///
+    /// 指定 `parent_id` 时任务成为该任务的子任务，清单由父任务决定；否则成为 `list_id` 中的根任务，
+    /// `list_id` 缺省时留在原清单。
+    pub fn move_task(
+        db: &Database,
+        task_id: &str,
+        list_id: Option<&str>,
+        parent_id: Option<&str>,
+        position: Option<i32>,
+    ) -> Result<Task> {
+        let task = TaskRepository::get_by_id(db, task_id)?;
+        let list_id = list_id.map(ListService::resolve_task_list).transpose()?;
+
+        let mut v = validation::Validator::new();
+        let target_list = match parent_id {
+            Some(parent_id) if parent_id == task_id || TaskRepository::is_descendant(db, task_id, parent_id)? => {
+                v.error("parent_id", "task cannot be moved under its own subtree");
+                task.list_id.clone()
+            }
+            Some(parent_id) => match TaskRepository::get_by_id(db, parent_id) {
+                Ok(parent) => {
+                    if list_id.as_ref().is_some_and(|id| id != &parent.list_id) {
+                        v.error("list_id", "must match the list of the parent task");
+                    }
+                    parent.list_id
+                }
+                Err(AppError::NotFound(_)) => {
+                    v.error("parent_id", format!("task {} does not exist", parent_id));
+                    task.list_id.clone()
+                }
+                Err(e) => return Err(e),
+            },
+            None => list_id.unwrap_or_else(|| task.list_id.clone()),
+        };
+        match ListRepository::get_by_id(db, &target_list) {
+            Ok(_) => {}
+            Err(AppError::NotFound(_)) => v.error("list_id", format!("list {} does not exist", target_list)),
+            Err(e) => return Err(e),
+        }
+        if position.is_some_and(|p| p < 0) {
+            v.error("position", "must not be negative");
+        }
+        v.finish()?;
+
+        TaskRepository::move_subtree(db, task_id, &target_list, parent_id, position)
+    }
+
+    /// 切换完成状态，与批量完成 / 取消完成共用同一套规则
+    pub fn toggle(db: &Database, task_id: &str) -> Result<Task> {
+        let task = TaskRepository::get_by_id(db, task_id)?;
+        TaskRepository::set_completed(db, task_id, !task.completed)
+    }
+
+    pub fn duplicate(db: &Database, task_id: &str, options: &DuplicateOptions) -> Result<Task> {
+        TaskRepository::duplicate(db, task_id, options)
+    }
+
+    /// 对多个任务执行同一操作，返回逐个任务的结果
+    pub fn bulk_apply(db: &Database, task_ids: &[String], op: &BulkTaskOperation) -> Result<Vec<BulkItemResult>> {
+        let op = match op {
+            BulkTaskOperation::MoveToList { list_id } => BulkTaskOperation::MoveToList {
+                list_id: ListService::resolve_task_list(list_id)?,
+            },
+            other => other.clone(),
+        };
+        validation::validate_bulk_operation(db, task_ids, &op)?;
+
+        TaskRepository::bulk_apply(db, task_ids, &op)
+    }
+
+    /// 子任务的清单始终跟随父任务；父任务不存在时交由校验报告
+    fn inherit_parent_list(db: &Database, task: &mut Task) -> Result<()> {
+        if let Some(parent_id) = &task.parent_id {
+            match TaskRepository::get_by_id(db, parent_id) {
+                Ok(parent) => task.list_id = parent.list_id,
+                Err(AppError::NotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// 未指定清单时写入默认清单；有截止日期但未设置提醒时使用默认提醒
+    fn apply_defaults(task: &Task, settings: &Settings) -> Task {
+        let mut task = task.clone();
+        if task.list_id.is_empty() {
+            task.list_id = settings.default_list_id.clone();
+        }
+        if task.due_date.is_some() && task.reminder.is_none() {
+            task.reminder = settings.default_reminder_offset.clone();
+        }
+        task
+    }
+
+    /// 去除标题首尾空白，并使完成时间与完成状态保持一致
+    fn normalize(mut task: Task, now: i64) -> Task {
+        task.title = task.title.trim().to_string();
+        if task.completed {
+            task.completed_at = task.completed_at.or(Some(now));
+        } else {
+            task.completed_at = None;
+        }
+        task
+    }
+}
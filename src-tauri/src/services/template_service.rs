@@ -0,0 +1,247 @@
+use crate::db::{Database, ListRepository, TagRepository, TaskRepository, TemplateRepository};
+use crate::error::{AppError, Result};
+use crate::models::{
+    template::render_placeholders, DueOffset, List, Settings, Task, Template, TemplateInstance, TemplateList,
+    TemplateTask,
+};
+use crate::ordering;
+use crate::services::ListService;
+use crate::validation::{self, Validator};
+use chrono::{Datelike, Days, NaiveDate};
+use std::collections::HashMap;
+
+/// 模板业务规则：保存、从现有任务生成模板，以及实例化为真实任务
+pub struct TemplateService;
+
+impl TemplateService {
+    pub fn create(db: &Database, template: &Template) -> Result<Template> {
+        let now = chrono::Utc::now().timestamp();
+        let template = Template {
+            name: template.name.trim().to_string(),
+            created_at: now,
+            updated_at: now,
+            ..template.clone()
+        };
+        validation::validate_template(db, &template)?;
+        TemplateRepository::create(db, &template)
+    }
+
+    pub fn update(db: &Database, template: &Template) -> Result<Template> {
+        let existing = TemplateRepository::get_by_id(db, &template.id)?;
+        let template = Template {
+            name: template.name.trim().to_string(),
+            created_at: existing.created_at,
+            updated_at: chrono::Utc::now().timestamp(),
+            ..template.clone()
+        };
+        validation::validate_template(db, &template)?;
+        TemplateRepository::update(db, &template)
+    }
+
+    /// 把任务及其未删除的子任务保存为模板，截止日期转换为相对今天的偏移
+    pub fn save_task(db: &Database, settings: &Settings, task_id: &str, name: &str) -> Result<Template> {
+        let task = TaskRepository::get_by_id(db, task_id)?;
+        let node = Self::capture(db, settings, &task)?;
+        Self::create(db, &Self::new_template(name, None, vec![node]))
+    }
+
+    /// 把清单及其中的任务树保存为清单模板
+    pub fn save_list(db: &Database, settings: &Settings, list_id: &str, name: &str) -> Result<Template> {
+        let list = ListService::get_by_id(db, settings.locale, list_id)?;
+        let roots = TaskRepository::get_by_list(db, list_id, settings)?
+            .into_iter()
+            .filter(|task| task.parent_id.is_none())
+            .map(|task| Self::capture(db, settings, &task))
+            .collect::<Result<Vec<_>>>()?;
+
+        let template_list = TemplateList { name: list.name, icon: list.icon, color: list.color };
+        Self::create(db, &Self::new_template(name, Some(template_list), roots))
+    }
+
+    /// 实例化模板。
+    ///
+    /// 指定 `list_id` 时任务创建在该清单中；否则清单模板新建清单，任务模板写入默认清单。
+    /// `variables` 覆盖内置占位符 `date`、`year`、`month`、`week`。
+    pub fn instantiate(
+        db: &Database,
+        settings: &Settings,
+        template_id: &str,
+        list_id: Option<&str>,
+        variables: &HashMap<String, String>,
+    ) -> Result<TemplateInstance> {
+        let template = TemplateRepository::get_by_id(db, template_id)?;
+        let today = settings.today();
+
+        let mut vars = Self::builtin_variables(today);
+        vars.extend(variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        // 清单模板新建的清单与任务树在同一事务中写入
+        let (list_id, new_list) = match (list_id, &template.list) {
+            (Some(list_id), _) => {
+                let list_id = ListService::resolve_task_list(list_id)?;
+                Self::ensure_list(db, &list_id)?;
+                (list_id, None)
+            }
+            (None, Some(list)) => {
+                let list = List::new(render_placeholders(&list.name, &vars), list.icon.clone(), list.color.clone());
+                let list = ListService::prepare_new(&list)?;
+                (list.id.clone(), Some(list))
+            }
+            (None, None) => (settings.default_list_id.clone(), None),
+        };
+
+        // 模板保存后标签可能已被删除，实例化时忽略失效的标签并在结果中报告
+        let tag_ids: Vec<String> = TagRepository::get_all(db)?.into_iter().map(|tag| tag.id).collect();
+        let context = BuildContext { settings, today, list_id: &list_id, vars: &vars, tag_ids: &tag_ids };
+
+        let mut tasks = Vec::new();
+        let mut skipped_tags = Vec::new();
+        context.build(&template.tasks, None, &mut tasks, &mut skipped_tags)?;
+        // 占位符替换后标题可能为空或超长，写入前按普通任务的规则校验
+        validation::validate_new_tasks(&tasks)?;
+        let tasks = TaskRepository::create_tree(db, new_list.as_ref(), &tasks)?;
+
+        tracing::debug!(created = tasks.len(), skipped_tags = skipped_tags.len(), "template instantiated");
+
+        Ok(TemplateInstance { list_id, tasks, skipped_tags })
+    }
+
+    fn builtin_variables(today: NaiveDate) -> HashMap<String, String> {
+        HashMap::from([
+            ("date".to_string(), today.format("%Y-%m-%d").to_string()),
+            ("year".to_string(), today.format("%Y").to_string()),
+            ("month".to_string(), today.format("%m").to_string()),
+            ("week".to_string(), today.iso_week().week().to_string()),
+        ])
+    }
+
+    fn ensure_list(db: &Database, list_id: &str) -> Result<()> {
+        match ListRepository::get_by_id(db, list_id) {
+            Ok(_) => Ok(()),
+            Err(AppError::NotFound(_)) => {
+                let mut v = Validator::new();
+                v.error("list_id", format!("list {} does not exist", list_id));
+                v.finish()
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn new_template(name: &str, list: Option<TemplateList>, tasks: Vec<TemplateTask>) -> Template {
+        Template {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            list,
+            tasks,
+            created_at: 0,
+            updated_at: 0,
+            version: 1,
+        }
+    }
+
+    fn capture(db: &Database, settings: &Settings, task: &Task) -> Result<TemplateTask> {
+        let children = TaskRepository::get_by_parent(db, &task.id)?
+            .iter()
+            .map(|child| Self::capture(db, settings, child))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(TemplateTask {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            priority: task.priority,
+            due_offset: task.due_date.map(|due| Self::offset_from_now(settings, due)),
+            reminder: task.reminder.clone(),
+            estimated_minutes: task.estimated_minutes,
+            repeat_rule: task.repeat_rule.clone(),
+            tags: task.tags.clone(),
+            children,
+        })
+    }
+
+    /// 截止日期恰好在某天零点时按天记录，否则按分钟记录
+    fn offset_from_now(settings: &Settings, due: i64) -> String {
+        let tz = settings.tz();
+        let due_date = chrono::DateTime::from_timestamp(due, 0)
+            .map(|dt| dt.with_timezone(&tz).date_naive())
+            .unwrap_or_else(|| settings.today());
+
+        if settings.day_bounds(due_date).0 == due {
+            format!("{:+}d", (due_date - settings.today()).num_days())
+        } else {
+            format!("{:+}m", (due - chrono::Utc::now().timestamp()) / 60)
+        }
+    }
+}
+
+/// 实例化时构建任务所需的上下文
+struct BuildContext<'a> {
+    settings: &'a Settings,
+    today: NaiveDate,
+    list_id: &'a str,
+    vars: &'a HashMap<String, String>,
+    tag_ids: &'a [String],
+}
+
+impl BuildContext<'_> {
+    /// 按先父后子的顺序展开模板任务树，同级任务依次分配排序键
+    fn build(
+        &self,
+        nodes: &[TemplateTask],
+        parent_id: Option<&str>,
+        out: &mut Vec<Task>,
+        skipped_tags: &mut Vec<String>,
+    ) -> Result<()> {
+        let keys = ordering::spread_keys(nodes.len());
+
+        for (node, sort_key) in nodes.iter().zip(keys) {
+            let mut task = Task::new(render_placeholders(&node.title, self.vars), self.list_id.to_string());
+            task.description = render_placeholders(&node.description, self.vars);
+            task.priority = node.priority;
+            task.parent_id = parent_id.map(str::to_string);
+            task.due_date = node
+                .due_offset
+                .as_deref()
+                .and_then(DueOffset::parse)
+                .map(|offset| self.due_date(offset))
+                .transpose()?;
+            task.reminder = node.reminder.clone();
+            if task.due_date.is_some() && task.reminder.is_none() {
+                task.reminder = self.settings.default_reminder_offset.clone();
+            }
+            task.estimated_minutes = node.estimated_minutes;
+            task.repeat_rule = node.repeat_rule.clone();
+            for tag_id in &node.tags {
+                if self.tag_ids.contains(tag_id) {
+                    task.tags.push(tag_id.clone());
+                } else if !skipped_tags.contains(tag_id) {
+                    skipped_tags.push(tag_id.clone());
+                }
+            }
+            task.sort_key = sort_key;
+
+            let id = task.id.clone();
+            out.push(task);
+            self.build(&node.children, Some(&id), out, skipped_tags)?;
+        }
+
+        Ok(())
+    }
+
+    fn due_date(&self, offset: DueOffset) -> Result<i64> {
+        let out_of_range = || AppError::InvalidInput("Due offset is out of range".to_string());
+        match offset {
+            DueOffset::Days(days) => {
+                let date = if days >= 0 {
+                    self.today.checked_add_days(Days::new(days.unsigned_abs()))
+                } else {
+                    self.today.checked_sub_days(Days::new(days.unsigned_abs()))
+                };
+                Ok(self.settings.day_bounds(date.ok_or_else(out_of_range)?).0)
+            }
+            DueOffset::Minutes(minutes) => minutes
+                .checked_mul(60)
+                .and_then(|secs| chrono::Utc::now().timestamp().checked_add(secs))
+                .ok_or_else(out_of_range),
+        }
+    }
+}
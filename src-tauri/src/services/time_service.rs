@@ -0,0 +1,80 @@
+use crate::db::{Database, TaskRepository, TimeEntryRepository};
+use crate::error::{AppError, Result};
+use crate::models::{Settings, TaskTimeSummary, TimeEntry, TimeReport, TimeReportRow};
+use crate::services::ListService;
+use crate::validation::{self, Validator, MAX_DESCRIPTION_LEN};
+use chrono::NaiveDate;
+
+/// 任务计时、预估汇总与报表
+pub struct TimeService;
+
+impl TimeService {
+    /// 为任务开始计时，同一时间只有一条计时中的记录
+    pub fn start(db: &Database, task_id: &str) -> Result<TimeEntry> {
+        Self::ensure_active_task(db, task_id)?;
+        TimeEntryRepository::start(db, task_id, chrono::Utc::now().timestamp())
+    }
+
+    pub fn stop(db: &Database) -> Result<TimeEntry> {
+        TimeEntryRepository::stop(db, chrono::Utc::now().timestamp())?
+            .ok_or_else(|| AppError::InvalidInput("No timer is running".to_string()))
+    }
+
+    /// 手动补录一段已结束的计时
+    pub fn log(db: &Database, task_id: &str, started_at: i64, ended_at: i64, note: &str) -> Result<TimeEntry> {
+        Self::ensure_active_task(db, task_id)?;
+
+        let mut v = Validator::new();
+        v.timestamp("started_at", Some(started_at));
+        v.timestamp("ended_at", Some(ended_at));
+        if ended_at <= started_at {
+            v.error("ended_at", "must be after started_at");
+        }
+        v.max_len("note", note, MAX_DESCRIPTION_LEN);
+        v.finish()?;
+
+        let entry = TimeEntry::new(task_id.to_string(), started_at, Some(ended_at), note.trim().to_string());
+        TimeEntryRepository::create(db, &entry)
+    }
+
+    /// 任务的预估与实际耗时，包含子孙任务的汇总
+    pub fn summary(db: &Database, task_id: &str) -> Result<TaskTimeSummary> {
+        let task = TaskRepository::get_by_id(db, task_id)?;
+        let (tracked_secs, total_estimated_minutes, total_tracked_secs) =
+            TimeEntryRepository::rollup(db, task_id, chrono::Utc::now().timestamp())?;
+        let running_entry = TimeEntryRepository::get_running(db)?.filter(|entry| entry.task_id == task_id);
+
+        Ok(TaskTimeSummary {
+            task_id: task.id,
+            estimated_minutes: task.estimated_minutes,
+            tracked_secs,
+            total_estimated_minutes,
+            total_tracked_secs,
+            running_entry,
+        })
+    }
+
+    /// 按清单与标签统计 `[start_date, end_date]`（用户时区）内的预估与实际耗时
+    pub fn report(db: &Database, settings: &Settings, start_date: NaiveDate, end_date: NaiveDate) -> Result<TimeReport> {
+        validation::validate_date_range(start_date, end_date)?;
+
+        let (start, _) = settings.day_bounds(start_date);
+        let (_, end) = settings.day_bounds(end_date);
+        let now = chrono::Utc::now().timestamp();
+
+        let by_list = TimeEntryRepository::report_by_list(db, start, end, now)?
+            .into_iter()
+            .map(|row| TimeReportRow { name: ListService::localized_name(settings.locale, &row.id, &row.name), ..row })
+            .collect();
+        let by_tag = TimeEntryRepository::report_by_tag(db, start, end, now)?;
+
+        Ok(TimeReport { by_list, by_tag })
+    }
+
+    fn ensure_active_task(db: &Database, task_id: &str) -> Result<()> {
+        if TaskRepository::get_by_id(db, task_id)?.is_deleted {
+            return Err(AppError::InvalidInput(format!("Task {} is in the trash", task_id)));
+        }
+        Ok(())
+    }
+}
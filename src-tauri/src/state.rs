@@ -1,15 +1,31 @@
 use crate::db::Database;
-use std::sync::Arc;
+use crate::models::{OverdueReport, Settings};
+use crate::services::FocusTimer;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::watch;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<Database>,
+    /// 当前生效的设置；后台任务通过 `subscribe` 感知变更
+    pub settings: Arc<watch::Sender<Settings>>,
+    /// 番茄钟计时器，由后台任务推进
+    pub focus: Arc<FocusTimer>,
+    /// 启动时生成的逾期报告，前端就绪后读取
+    pub overdue_report: Arc<OnceLock<OverdueReport>>,
 }
 
 impl AppState {
-    pub fn new(db: Database) -> Self {
+    pub fn new(db: Database, settings: Settings) -> Self {
         Self {
             db: Arc::new(db),
+            settings: Arc::new(watch::Sender::new(settings)),
+            focus: Arc::new(FocusTimer::default()),
+            overdue_report: Arc::new(OnceLock::new()),
         }
     }
+
+    pub fn settings(&self) -> Settings {
+        self.settings.borrow().clone()
+    }
 }
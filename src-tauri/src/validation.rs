@@ -0,0 +1,395 @@
+use crate::db::{Database, ListRepository, SectionRepository, TagRepository, TaskRepository};
+use crate::error::{AppError, FieldError, Result};
+use crate::models::template::MAX_DUE_OFFSET_DAYS;
+use crate::models::{
+    BulkTaskOperation, DueOffset, Habit, List, RepeatRule, Section, Tag, Task, TaskPatch, Template, TemplateTask,
+};
+use chrono::{Days, NaiveDate};
+
+pub const MAX_TITLE_LEN: usize = 500;
+pub const MAX_DESCRIPTION_LEN: usize = 20_000;
+pub const MAX_LIST_NAME_LEN: usize = 100;
+pub const MAX_SECTION_NAME_LEN: usize = 100;
+pub const MAX_TAG_NAME_LEN: usize = 64;
+pub const MAX_ICON_LEN: usize = 16;
+pub const MAX_REMINDER_LEN: usize = 64;
+pub const MAX_BULK_TASKS: usize = 1000;
+pub const MAX_TEMPLATE_NAME_LEN: usize = 100;
+pub const MAX_TEMPLATE_TASKS: usize = 500;
+/// 预估耗时上限：30 天
+pub const MAX_ESTIMATED_MINUTES: i32 = 30 * 24 * 60;
+/// 时间块时长上限：7 天
+pub const MAX_DURATION_MINUTES: i32 = 7 * 24 * 60;
+pub const MAX_HABIT_NAME_LEN: usize = 100;
+pub const MAX_HABIT_GOAL_COUNT: i32 = 100;
+/// 单日打卡次数上限，允许超出每日目标
+pub const MAX_HABIT_CHECK_INS: i32 = 999;
+/// 习惯开始日期距今天的最大天数
+pub const MAX_HABIT_START_OFFSET_DAYS: u64 = 3650;
+
+/// 统计类查询单次允许的最大天数
+pub const MAX_DATE_RANGE_DAYS: u64 = 366;
+
+/// 允许的最大时间戳（9999-12-31T23:59:59Z）
+const MAX_TIMESTAMP: i64 = 253_402_300_799;
+
+/// 收集字段级错误，最后统一返回 `AppError::Validation`
+#[derive(Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn error(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError::new(field, message));
+    }
+
+    /// 去除首尾空白后不能为空，且不超过最大长度
+    pub fn required_text(&mut self, field: &str, value: &str, max_len: usize) {
+        if value.trim().is_empty() {
+            self.error(field, "must not be empty");
+        }
+        self.max_len(field, value, max_len);
+    }
+
+    pub fn max_len(&mut self, field: &str, value: &str, max_len: usize) {
+        if value.chars().count() > max_len {
+            self.error(field, format!("must be at most {} characters", max_len));
+        }
+    }
+
+    /// 颜色需为 `#RGB` / `#RRGGBB` / `#RRGGBBAA`，或前端主题变量 `var(--name)`
+    pub fn color(&mut self, field: &str, value: &str) {
+        if !is_valid_color(value) {
+            self.error(field, "must be a hex color such as #3B82F6 or a theme variable such as var(--dida-primary)");
+        }
+    }
+
+    pub fn timestamp(&mut self, field: &str, value: Option<i64>) {
+        if let Some(ts) = value {
+            if !(0..=MAX_TIMESTAMP).contains(&ts) {
+                self.error(field, "must be a valid unix timestamp");
+            }
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.errors))
+        }
+    }
+}
+
+fn is_valid_color(value: &str) -> bool {
+    if let Some(hex) = value.strip_prefix('#') {
+        return matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    if let Some(name) = value.strip_prefix("var(--").and_then(|v| v.strip_suffix(')')) {
+        return !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    }
+    false
+}
+
+/// 校验任务内容及其引用的清单、父任务与标签
+pub fn validate_task(db: &Database, task: &Task) -> Result<()> {
+    let mut v = Validator::new();
+
+    check_task_fields(db, &mut v, task)?;
+    check_tags(db, &mut v, "tags", &task.tags)?;
+
+    v.finish()
+}
+
+/// 校验应用部分更新后的任务；新增的标签以 `add_tags[i]` 报告
+pub fn validate_task_patch(db: &Database, patched: &Task, patch: &TaskPatch) -> Result<()> {
+    let mut v = Validator::new();
+
+    check_task_fields(db, &mut v, patched)?;
+    check_tags(db, &mut v, "add_tags", &patch.add_tags)?;
+
+    v.finish()
+}
+
+/// 校验从模板展开、尚未写入的任务树。清单与父任务随任务树一起创建，只检查任务内容，
+/// 错误以 `tasks[i].<field>` 报告
+pub fn validate_new_tasks(tasks: &[Task]) -> Result<()> {
+    let mut v = Validator::new();
+
+    for (index, task) in tasks.iter().enumerate() {
+        check_task_content(&mut v, &format!("tasks[{}].", index), task);
+    }
+
+    v.finish()
+}
+
+fn check_task_fields(db: &Database, v: &mut Validator, task: &Task) -> Result<()> {
+    check_task_content(v, "", task);
+
+    if exists(ListRepository::get_by_id(db, &task.list_id))?.is_none() {
+        v.error("list_id", format!("list {} does not exist", task.list_id));
+    }
+
+    if let Some(parent_id) = &task.parent_id {
+        if parent_id == &task.id {
+            v.error("parent_id", "task cannot be its own parent");
+        } else if exists(TaskRepository::get_by_id(db, parent_id))?.is_none() {
+            v.error("parent_id", format!("task {} does not exist", parent_id));
+        } else if TaskRepository::is_descendant(db, &task.id, parent_id)? {
+            v.error("parent_id", "task cannot be moved under its own subtree");
+        }
+    }
+
+    if let Some(section_id) = &task.section_id {
+        match exists(SectionRepository::get_by_id(db, section_id))? {
+            None => v.error("section_id", format!("section {} does not exist", section_id)),
+            Some(section) if section.list_id != task.list_id => {
+                v.error("section_id", format!("section {} belongs to another list", section_id));
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// 不依赖数据库的任务字段校验，字段名加上 `prefix` 前缀
+fn check_task_content(v: &mut Validator, prefix: &str, task: &Task) {
+    let field = |name: &str| format!("{}{}", prefix, name);
+
+    v.required_text(&field("title"), &task.title, MAX_TITLE_LEN);
+    v.max_len(&field("description"), &task.description, MAX_DESCRIPTION_LEN);
+    v.timestamp(&field("due_date"), task.due_date);
+    v.timestamp(&field("completed_at"), task.completed_at);
+    v.timestamp(&field("start_date"), task.start_date);
+    if let Some(minutes) = task.duration_minutes {
+        if !(1..=MAX_DURATION_MINUTES).contains(&minutes) {
+            v.error(field("duration_minutes"), format!("must be between 1 and {}", MAX_DURATION_MINUTES));
+        } else if task.is_all_day {
+            v.error(field("duration_minutes"), "must be empty for all-day tasks");
+        } else if task.start_date.is_none() {
+            v.error(field("duration_minutes"), "requires start_date");
+        }
+    }
+    if let Some(minutes) = task.estimated_minutes {
+        if !(0..=MAX_ESTIMATED_MINUTES).contains(&minutes) {
+            v.error(field("estimated_minutes"), format!("must be between 0 and {}", MAX_ESTIMATED_MINUTES));
+        }
+    }
+    if let Some(reminder) = &task.reminder {
+        v.max_len(&field("reminder"), reminder, MAX_REMINDER_LEN);
+    }
+    if let Some(rule) = &task.repeat_rule {
+        validate_repeat_rule(v, &field("repeat_rule"), rule);
+    }
+}
+
+fn check_tags(db: &Database, v: &mut Validator, field: &str, tag_ids: &[String]) -> Result<()> {
+    for (index, tag_id) in tag_ids.iter().enumerate() {
+        if exists(TagRepository::get_by_id(db, tag_id))?.is_none() {
+            v.error(format!("{}[{}]", field, index), format!("tag {} does not exist", tag_id));
+        }
+    }
+    Ok(())
+}
+
+/// 校验批量操作本身；任务是否存在在执行时逐个报告
+pub fn validate_bulk_operation(db: &Database, task_ids: &[String], op: &BulkTaskOperation) -> Result<()> {
+    let mut v = Validator::new();
+
+    if task_ids.len() > MAX_BULK_TASKS {
+        v.error("task_ids", format!("must contain at most {} tasks", MAX_BULK_TASKS));
+    }
+
+    match op {
+        BulkTaskOperation::MoveToList { list_id } if exists(ListRepository::get_by_id(db, list_id))?.is_none() => {
+            v.error("operation.list_id", format!("list {} does not exist", list_id));
+        }
+        BulkTaskOperation::SetDueDate { due_date } => v.timestamp("operation.due_date", *due_date),
+        BulkTaskOperation::AddTag { tag_id } if exists(TagRepository::get_by_id(db, tag_id))?.is_none() => {
+            v.error("operation.tag_id", format!("tag {} does not exist", tag_id));
+        }
+        _ => {}
+    }
+
+    v.finish()
+}
+
+/// 校验统计查询的日期范围（闭区间）
+pub fn validate_date_range(start_date: NaiveDate, end_date: NaiveDate) -> Result<()> {
+    if end_date < start_date {
+        return Err(AppError::InvalidInput("end_date must not be before start_date".to_string()));
+    }
+    if start_date.checked_add_days(Days::new(MAX_DATE_RANGE_DAYS)).is_some_and(|limit| end_date >= limit) {
+        return Err(AppError::InvalidInput(format!(
+            "Date range must not exceed {} days",
+            MAX_DATE_RANGE_DAYS
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_repeat_rule(v: &mut Validator, field: &str, rule: &RepeatRule) {
+    if let Some(interval) = rule.interval {
+        if !(1..=999).contains(&interval) {
+            v.error(format!("{}.interval", field), "must be between 1 and 999");
+        }
+    }
+    if let Some(days) = &rule.days_of_week {
+        for (index, day) in days.iter().enumerate() {
+            if !(0..=6).contains(day) {
+                v.error(format!("{}.daysOfWeek[{}]", field, index), "must be between 0 and 6");
+            }
+        }
+    }
+    if let Some(day) = rule.day_of_month {
+        if !(1..=31).contains(&day) {
+            v.error(format!("{}.dayOfMonth", field), "must be between 1 and 31");
+        }
+    }
+    if let Some(month) = rule.month_of_year {
+        if !(1..=12).contains(&month) {
+            v.error(format!("{}.monthOfYear", field), "must be between 1 and 12");
+        }
+    }
+    v.timestamp(&format!("{}.endDate", field), rule.end_date);
+}
+
+pub fn validate_list(list: &List) -> Result<()> {
+    let mut v = Validator::new();
+
+    v.required_text("name", &list.name, MAX_LIST_NAME_LEN);
+    v.max_len("icon", &list.icon, MAX_ICON_LEN);
+    v.color("color", &list.color);
+
+    v.finish()
+}
+
+pub fn validate_section(section: &Section) -> Result<()> {
+    let mut v = Validator::new();
+
+    v.required_text("name", &section.name, MAX_SECTION_NAME_LEN);
+
+    v.finish()
+}
+
+pub fn validate_tag(db: &Database, tag: &Tag) -> Result<()> {
+    let mut v = Validator::new();
+
+    v.required_text("name", &tag.name, MAX_TAG_NAME_LEN);
+    v.color("color", &tag.color);
+    if let Some(existing) = TagRepository::find_by_name(db, &tag.name)? {
+        if existing.id != tag.id {
+            v.error("name", format!("tag {} already exists", existing.name));
+        }
+    }
+
+    if let Some(parent_id) = &tag.parent_id {
+        if parent_id == &tag.id {
+            v.error("parent_id", "tag cannot be its own parent");
+        } else if exists(TagRepository::get_by_id(db, parent_id))?.is_none() {
+            v.error("parent_id", format!("tag {} does not exist", parent_id));
+        } else if TagRepository::is_in_subtree(db, &tag.id, parent_id)? {
+            v.error("parent_id", "tag cannot be moved under its own descendant");
+        }
+    }
+
+    v.finish()
+}
+
+pub fn validate_habit(habit: &Habit, today: NaiveDate) -> Result<()> {
+    let mut v = Validator::new();
+
+    v.required_text("name", &habit.name, MAX_HABIT_NAME_LEN);
+    v.max_len("icon", &habit.icon, MAX_ICON_LEN);
+    v.color("color", &habit.color);
+    if !(1..=MAX_HABIT_GOAL_COUNT).contains(&habit.goal_count) {
+        v.error("goal_count", format!("must be between 1 and {}", MAX_HABIT_GOAL_COUNT));
+    }
+    validate_repeat_rule(&mut v, "frequency", &habit.frequency);
+    let offset = Days::new(MAX_HABIT_START_OFFSET_DAYS);
+    let earliest = today.checked_sub_days(offset).unwrap_or(NaiveDate::MIN);
+    let latest = today.checked_add_days(offset).unwrap_or(NaiveDate::MAX);
+    if !(earliest..=latest).contains(&habit.start_date) {
+        v.error("start_date", format!("must be within {} days of today", MAX_HABIT_START_OFFSET_DAYS));
+    }
+
+    v.finish()
+}
+
+/// 校验模板；占位符在实例化时才替换，这里校验的是原始文本
+pub fn validate_template(db: &Database, template: &Template) -> Result<()> {
+    let mut v = Validator::new();
+
+    v.required_text("name", &template.name, MAX_TEMPLATE_NAME_LEN);
+    if let Some(list) = &template.list {
+        v.required_text("list.name", &list.name, MAX_LIST_NAME_LEN);
+        v.max_len("list.icon", &list.icon, MAX_ICON_LEN);
+        v.color("list.color", &list.color);
+    } else if template.tasks.is_empty() {
+        v.error("tasks", "must not be empty");
+    }
+
+    let tag_ids: Vec<String> = TagRepository::get_all(db)?.into_iter().map(|tag| tag.id).collect();
+    let mut count = 0;
+    for (index, task) in template.tasks.iter().enumerate() {
+        check_template_task(&mut v, &format!("tasks[{}]", index), task, &tag_ids, &mut count);
+    }
+    if count > MAX_TEMPLATE_TASKS {
+        v.error("tasks", format!("must contain at most {} tasks", MAX_TEMPLATE_TASKS));
+    }
+
+    v.finish()
+}
+
+fn check_template_task(v: &mut Validator, path: &str, task: &TemplateTask, tag_ids: &[String], count: &mut usize) {
+    *count += 1;
+
+    v.required_text(&format!("{}.title", path), &task.title, MAX_TITLE_LEN);
+    v.max_len(&format!("{}.description", path), &task.description, MAX_DESCRIPTION_LEN);
+    if let Some(offset) = &task.due_offset {
+        if DueOffset::parse(offset).is_none() {
+            v.error(
+                format!("{}.due_offset", path),
+                format!("must look like +2d, -1w, +3h or 30m and be within {} days", MAX_DUE_OFFSET_DAYS),
+            );
+        }
+    }
+    if let Some(minutes) = task.estimated_minutes {
+        if !(0..=MAX_ESTIMATED_MINUTES).contains(&minutes) {
+            v.error(
+                format!("{}.estimated_minutes", path),
+                format!("must be between 0 and {}", MAX_ESTIMATED_MINUTES),
+            );
+        }
+    }
+    if let Some(reminder) = &task.reminder {
+        v.max_len(&format!("{}.reminder", path), reminder, MAX_REMINDER_LEN);
+    }
+    if let Some(rule) = &task.repeat_rule {
+        validate_repeat_rule(v, &format!("{}.repeat_rule", path), rule);
+    }
+    for (index, tag_id) in task.tags.iter().enumerate() {
+        if !tag_ids.contains(tag_id) {
+            v.error(format!("{}.tags[{}]", path, index), format!("tag {} does not exist", tag_id));
+        }
+    }
+
+    for (index, child) in task.children.iter().enumerate() {
+        check_template_task(v, &format!("{}.children[{}]", path, index), child, tag_ids, count);
+    }
+}
+
+/// 把 NotFound 转成 `None`，其余错误继续向上传递
+fn exists<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(AppError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
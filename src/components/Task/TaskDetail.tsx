@@ -1,4 +1,4 @@
-import { useTask, useSubtasks, useCreateSubtaskSimple, useUpdateTaskOrders, useUpdateTask, useUndoDeleteTask, useDeleteTaskPermanently, useDeleteTask, useToggleTask } from '../../hooks/useTasks';
+import { useTask, useSubtasks, useCreateSubtaskSimple, useReorderTask, useUpdateTask, useUndoDeleteTask, useDeleteTaskPermanently, useDeleteTask, useToggleTask } from '../../hooks/useTasks';
 import { useTags, useCreateTag } from '../../hooks/useTags';
 import { useAppStore } from '../../store/useAppStore';
 import { useAlertStore } from '../../store/useAlertStore';
@@ -73,7 +73,7 @@ export default function TaskDetail() {
   const createTag = useCreateTag();
   const { data: allLists } = useLists();
   const createSubtask = useCreateSubtaskSimple();
-  const updateTaskOrders = useUpdateTaskOrders();
+  const reorderTask = useReorderTask();
   const updateTask = useUpdateTask();
   const undoDeleteTask = useUndoDeleteTask();
   const deleteTaskPermanently = useDeleteTaskPermanently();
@@ -309,12 +309,11 @@ const handleDescriptionBlur = () => {
     const newSubtasks = arrayMove(localSubtasks, oldIndex, newIndex);
     setLocalSubtasks(newSubtasks);
 
-    const ascendingOrders: [string, number][] = newSubtasks.map((t, index) => [
-      t.id,
-      index * 10,
-    ]);
-
-    updateTaskOrders.mutate(ascendingOrders);
+    reorderTask.mutate({
+      taskId: String(active.id),
+      prevId: newSubtasks[newIndex - 1]?.id ?? null,
+      nextId: newSubtasks[newIndex + 1]?.id ?? null,
+    });
   };
 
   const handleAddSubtask = (e: React.FormEvent) => {
@@ -14,7 +14,7 @@ import {
 } from '@dnd-kit/sortable';
 import { restrictToVerticalAxis } from '@dnd-kit/modifiers';
 import { Plus, Calendar, ChevronDown, Flag, Hash, X, Check, Trash2, Inbox, Paperclip, Copy, Settings, ChevronRight, PanelLeftClose, PanelLeftOpen } from 'lucide-react';
-import { useTasks, useCreateTaskExtended, useUpdateTaskOrders, useEmptyTrash } from '../../hooks/useTasks';
+import { useTasks, useCreateTaskExtended, useReorderTask, useEmptyTrash } from '../../hooks/useTasks';
 import { useTags, useCreateTag, useUpdateTag } from '../../hooks/useTags';
 import { useLists } from '../../hooks/useLists';
 import { Task, Priority, RepeatRule } from '../../types';
@@ -67,7 +67,7 @@ export default function TaskList() {
   const createTask = useCreateTaskExtended();
   const createTag = useCreateTag();
   const updateTag = useUpdateTag();
-  const updateTaskOrders = useUpdateTaskOrders();
+  const reorderTask = useReorderTask();
   const emptyTrash = useEmptyTrash();
   const { showAlert } = useAlertStore();
 
@@ -159,8 +159,8 @@ export default function TaskList() {
       }
     }
     if (newTaskTitle.trim()) {
-      // 如果是“所有任务”视图或没有选择清单，默认为收集箱 (inbox)
-      const listId = (selectedListId === 'smart_all' || !selectedListId) ? 'smart_inbox' : selectedListId;
+      // 智能清单只是视图，不能直接承载任务；在智能清单或未选择清单时默认为收集箱 (inbox)
+      const listId = (!selectedListId || selectedListId.startsWith('smart_')) ? 'smart_inbox' : selectedListId;
       let title = newTaskTitle.trim();
       const tagsToAssign = new Set(newTaskTags);
 
@@ -243,12 +243,12 @@ export default function TaskList() {
     const newTasks = arrayMove(localTasks, oldIndex, newIndex);
     setLocalTasks(newTasks);
 
-    const ascendingOrders: [string, number][] = newTasks.map((t, index) => [
-      t.id,
-      index * 10,
-    ]);
-
-    updateTaskOrders.mutate(ascendingOrders);
+    // 只提交被拖动任务的新邻居，后端据此生成排序键
+    reorderTask.mutate({
+      taskId: String(active.id),
+      prevId: newTasks[newIndex - 1]?.id ?? null,
+      nextId: newTasks[newIndex + 1]?.id ?? null,
+    });
   };
 
   const isTrashView = selectedListId === SMART_LIST_IDS.TRASH;
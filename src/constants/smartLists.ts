@@ -1,4 +1,4 @@
-import { Inbox, Calendar, CalendarDays, ClipboardList, CheckCircle2, Trash2 } from 'lucide-react';
+import { Inbox, Calendar, CalendarDays, ClipboardList, CheckCircle2, Trash2, AlarmClock } from 'lucide-react';
 
 export const SMART_LIST_IDS = {
   INBOX: 'smart_inbox',
@@ -7,6 +7,7 @@ export const SMART_LIST_IDS = {
   WEEK: 'smart_week',
   COMPLETED: 'smart_completed',
   TRASH: 'smart_trash',
+  OVERDUE: 'smart_overdue',
 } as const;
 
 export const SMART_LIST_CONFIG = {
@@ -34,4 +35,8 @@ export const SMART_LIST_CONFIG = {
     icon: Trash2,
     label: '垃圾桶',
   },
+  [SMART_LIST_IDS.OVERDUE]: {
+    icon: AlarmClock,
+    label: '已过期',
+  },
 };
@@ -106,6 +106,19 @@ export function useCreateSubtaskSimple() {
   });
 }
 
+export function useReorderTask() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ taskId, prevId, nextId }: { taskId: string; prevId: string | null; nextId: string | null }) =>
+      taskService.reorderTask(taskId, prevId, nextId),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['tasks'] });
+      queryClient.invalidateQueries({ queryKey: ['subtasks'] });
+    },
+  });
+}
+
 export function useUpdateTaskOrders() {
   const queryClient = useQueryClient();
 
@@ -285,6 +285,21 @@ const browserTaskStore = {
     }));
     saveBrowserTasks(next);
   },
+
+  async reorderTask(taskId: string, prevId: string | null, nextId: string | null): Promise<Task> {
+    const tasks = loadBrowserTasks();
+    const prev = tasks.find(t => t.id === prevId);
+    const next = tasks.find(t => t.id === nextId);
+    const order = prev && next
+      ? (prev.order + next.order) / 2
+      : prev ? prev.order + 10 : next ? next.order - 10 : 0;
+    const target = tasks.find(t => t.id === taskId);
+    if (!target) throw new Error(`Task ${taskId} not found`);
+    target.order = order;
+    target.updated_at = Math.floor(Date.now() / 1000);
+    saveBrowserTasks(tasks);
+    return target;
+  },
 };
 
 export const taskService = {
@@ -348,6 +363,11 @@ export const taskService = {
     return await invoke('update_task_orders', { orders });
   },
 
+  async reorderTask(taskId: string, prevId: string | null, nextId: string | null): Promise<Task> {
+    if (!isTauriEnv()) return browserTaskStore.reorderTask(taskId, prevId, nextId);
+    return await invoke('reorder_task', { taskId, prevId, nextId });
+  },
+
   async getTasksByTag(tagId: string): Promise<Task[]> {
     if (!isTauriEnv()) return browserTaskStore.getTasksByTag(tagId);
     return await invoke('get_tasks_by_tag', { tagId });
@@ -0,0 +1,27 @@
+export type AppErrorCode =
+  | 'database'
+  | 'not_found'
+  | 'invalid_input'
+  | 'validation'
+  | 'conflict'
+  | 'foreign_key'
+  | 'busy'
+  | 'protected_list'
+  | 'invalid_list_target'
+  | 'io'
+  | 'serialization';
+
+export interface FieldError {
+  field: string;
+  message: string;
+}
+
+// 后端命令失败时 invoke 抛出的错误结构
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  details: {
+    fields?: FieldError[];
+    current?: unknown;
+  } | null;
+}
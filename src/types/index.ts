@@ -1,3 +1,5 @@
 export * from './task';
 export * from './list';
 export * from './tag';
+export * from './section';
+export * from './error';
@@ -5,7 +5,12 @@ export interface List {
   color: string;
   is_smart: boolean;
   order: number;
+  sort_key?: string;
+  is_archived?: boolean;
+  is_deleted?: boolean;
+  is_hidden?: boolean;
   created_at: number;
+  version?: number;
 }
 
 export enum SmartListType {
@@ -15,4 +20,5 @@ export enum SmartListType {
   All = 'smart_all',
   Completed = 'smart_completed',
   Trash = 'smart_trash',
+  Overdue = 'smart_overdue',
 }
@@ -0,0 +1,17 @@
+import { Task } from './task';
+
+export interface Section {
+  id: string;
+  list_id: string;
+  name: string;
+  sort_key?: string;
+  created_at: number;
+  updated_at: number;
+  version?: number;
+}
+
+// 看板中的一列，section 为 null 的一列是未分组的任务
+export interface SectionGroup {
+  section: Section | null;
+  tasks: Task[];
+}
@@ -5,4 +5,9 @@ export interface Tag {
   parent_id?: string | null;
   is_pinned?: boolean;
   created_at: number;
+  version?: number;
+  sort_key?: string;
+  open_count?: number;
+  completed_count?: number;
+  last_used_at?: number | null;
 }
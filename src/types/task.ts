@@ -37,10 +37,18 @@ export interface Task {
   tags: string[];
   parent_id: string | null;
   order: number;
+  sort_key?: string;
   is_deleted: boolean;
   created_at: number;
   updated_at: number;
   completed_at: number | null;
+  estimated_minutes?: number | null;
+  start_date?: number | null;
+  duration_minutes?: number | null;
+  is_all_day?: boolean;
+  section_id?: string | null;
+  is_overdue?: boolean;
+  version?: number;
 }
 
 export interface CreateTaskInput {